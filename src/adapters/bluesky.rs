@@ -0,0 +1,546 @@
+//! Puller for Bluesky and other AT Protocol services, using the XRPC API.
+//!
+//! Logs in with an app password and reads the account's post records straight
+//! from its repository. Posts have no title, tags or drafts: the first line of
+//! the text stands in for the title, and replies are left out.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::OnceCell;
+use url::Url;
+
+use super::{
+    check_response, send_with_retry, ArticleMetadata, HttpOptions, PullOptions, Puller,
+    RateLimiter, RetryConfig,
+};
+use crate::article::{ArticleStats, PulledArticle};
+use crate::error::{PullError, RequestContext, Result};
+use crate::platform::Platform;
+
+/// Service hosting accounts on the main Bluesky network.
+const DEFAULT_SERVICE_URL: &str = "https://bsky.social";
+
+/// Collection holding an account's posts.
+const POST_COLLECTION: &str = "app.bsky.feed.post";
+
+/// Records per page; the most `listRecords` returns at once.
+const PER_PAGE: u32 = 100;
+
+/// Longest title, in characters, taken from the first line of a post.
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Session {
+    access_jwt: String,
+    did: String,
+    handle: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordList {
+    records: Vec<PostRecord>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostRecord {
+    /// `at://{did}/app.bsky.feed.post/{rkey}`
+    uri: String,
+    value: Post,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Post {
+    #[serde(default)]
+    text: String,
+    created_at: DateTime<Utc>,
+    /// Only present on replies, whose content is left out
+    #[serde(default)]
+    reply: Option<serde_json::Value>,
+}
+
+impl PostRecord {
+    /// Record key, the last segment of the URI, which identifies the post.
+    fn rkey(&self) -> &str {
+        self.uri.rsplit('/').next().unwrap_or(&self.uri)
+    }
+
+    fn into_pulled_article(self, platform: Platform, handle: &str) -> PulledArticle {
+        let rkey = self.rkey().to_string();
+        PulledArticle {
+            title: post_title(&self.value.text, &rkey),
+            url: post_url(handle, &rkey),
+            platform_id: rkey,
+            platform,
+            body_markdown: self.value.text,
+            published_at: Some(self.value.created_at),
+            edited_at: None,
+            tags: Vec::new(),
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: Some(handle.to_string()),
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        }
+    }
+}
+
+/// Returns the first line of `text`, shortened to `MAX_TITLE_CHARS`, or `rkey`
+/// when the post has no text (e.g. only an image).
+fn post_title(text: &str, rkey: &str) -> String {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty());
+    let Some(line) = line else {
+        return rkey.to_string();
+    };
+    match line.char_indices().nth(MAX_TITLE_CHARS) {
+        Some((end, _)) => format!("{}…", line[..end].trim_end()),
+        None => line.to_string(),
+    }
+}
+
+/// Public bsky.app URL of a post.
+fn post_url(handle: &str, rkey: &str) -> Option<Url> {
+    Url::parse(&format!("https://bsky.app/profile/{handle}/post/{rkey}")).ok()
+}
+
+pub struct BlueskyPuller {
+    /// Handle or DID the account logs in with
+    handle: String,
+    service_url: String,
+    client: reqwest::Client,
+    app_password: String,
+    retry: RetryConfig,
+    limiter: RateLimiter,
+    session: OnceCell<Session>,
+}
+
+impl BlueskyPuller {
+    pub fn new(
+        handle: String,
+        app_password: String,
+        http: &HttpOptions,
+        retry: Option<RetryConfig>,
+    ) -> Result<Self> {
+        Ok(Self {
+            handle,
+            service_url: DEFAULT_SERVICE_URL.to_string(),
+            client: http.client(HeaderMap::new())?,
+            app_password,
+            retry: retry.unwrap_or_default(),
+            limiter: http.rate_limiter()?,
+            session: OnceCell::new(),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_service_url(mut self, service_url: &str) -> Self {
+        self.service_url = service_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Returns the login session, logging in on first call.
+    async fn session(&self) -> Result<&Session> {
+        self.session.get_or_try_init(|| self.create_session()).await
+    }
+
+    async fn create_session(&self) -> Result<Session> {
+        let url = format!("{}/xrpc/com.atproto.server.createSession", self.service_url);
+        let body = json!({"identifier": self.handle, "password": self.app_password});
+
+        async {
+            let response = send_with_retry(&url, &self.retry, &self.limiter, || {
+                self.client.post(&url).json(&body)
+            })
+            .await?;
+            // A wrong password is answered with 401 AuthenticationRequired
+            Ok(check_status(response, &self.platform())
+                .await?
+                .json()
+                .await?)
+        }
+        .await
+        .context("log in", &url)
+    }
+
+    /// Calls an XRPC query with the session token, returning `None` when the
+    /// record doesn't exist.
+    ///
+    /// `operation` describes the request in errors, e.g. "list page 3".
+    async fn get<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        method: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        let session = self.session().await?;
+        let mut url = Url::parse(&format!("{}/xrpc/{method}", self.service_url))
+            .map_err(|e| PullError::InvalidConfig(format!("Invalid Bluesky service URL: {e}")))?;
+        url.query_pairs_mut().extend_pairs(query);
+
+        async {
+            let response = send_with_retry(url.as_str(), &self.retry, &self.limiter, || {
+                self.client
+                    .get(url.clone())
+                    .bearer_auth(&session.access_jwt)
+            })
+            .await?;
+
+            // Missing records are a 400 with error RecordNotFound rather than a 404
+            if response.status().as_u16() == 400 {
+                let body = response.text().await.unwrap_or_default();
+                if body.contains("RecordNotFound") {
+                    return Ok(None);
+                }
+                return Err(PullError::api(400, &body));
+            }
+            if response.status().as_u16() == 404 {
+                return Ok(None);
+            }
+
+            Ok(Some(
+                check_status(response, &self.platform())
+                    .await?
+                    .json()
+                    .await?,
+            ))
+        }
+        .await
+        .context(operation, url.as_str())
+    }
+
+    /// Fetches one page of post records, newest first, keeping those that pass
+    /// `options`, and returns them with the cursor of the next page.
+    ///
+    /// `collected` is the number of posts listed on earlier pages.
+    async fn list_page(
+        &self,
+        options: &PullOptions,
+        cursor: Option<&str>,
+        page: u32,
+        collected: usize,
+    ) -> Result<(Vec<ArticleMetadata>, Option<String>)> {
+        if options.limit_reached(collected) {
+            return Ok((Vec::new(), None));
+        }
+
+        let session = self.session().await?;
+        let per_page = PER_PAGE.to_string();
+        let mut query = vec![
+            ("repo", session.did.as_str()),
+            ("collection", POST_COLLECTION),
+            ("limit", per_page.as_str()),
+        ];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        let records: RecordList = self
+            .get(
+                &format!("list page {page}"),
+                "com.atproto.repo.listRecords",
+                &query,
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(self.handle.clone()))?;
+
+        // Replies are left out before the limit counts the posts
+        let posts: Vec<_> = records
+            .records
+            .into_iter()
+            .filter(|record| record.value.reply.is_none())
+            .collect();
+        let (posts, done) = options.take_newest_first(page, collected, posts, |record| {
+            Some(record.value.created_at)
+        });
+        let listed = posts
+            .into_iter()
+            .map(|record| {
+                let rkey = record.rkey().to_string();
+                ArticleMetadata {
+                    title: post_title(&record.value.text, &rkey),
+                    platform: self.platform(),
+                    published_at: Some(record.value.created_at),
+                    edited_at: None,
+                    url: post_url(&session.handle, &rkey),
+                    slug: Some(rkey.clone()),
+                    id: rkey,
+                    is_draft: false,
+                }
+            })
+            .collect();
+        Ok((listed, records.cursor.filter(|_| !done)))
+    }
+}
+
+/// Maps a rejected session or password to `PullError::Unauthorized`, and other
+/// error statuses as `check_response` does.
+async fn check_status(
+    response: reqwest::Response,
+    platform: &Platform,
+) -> Result<reqwest::Response> {
+    if matches!(response.status().as_u16(), 401 | 403) {
+        return Err(PullError::Unauthorized(platform.to_string()));
+    }
+    check_response(response).await
+}
+
+#[async_trait]
+impl Puller for BlueskyPuller {
+    fn platform(&self) -> Platform {
+        Platform::Bluesky(self.handle.clone())
+    }
+
+    async fn verify_credentials(&self) -> Result<String> {
+        Ok(self.session().await?.handle.clone())
+    }
+
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        // Posts are public as soon as they are created
+        if options.drafts_only {
+            return stream::empty().boxed();
+        }
+
+        // Pages are chained by an opaque cursor rather than numbered, so this
+        // can't use `paginate`; `None` as the state ends the listing
+        stream::try_unfold(
+            Some((None::<String>, 1_u32, 0_usize)),
+            move |state| async move {
+                let Some((cursor, page, collected)) = state else {
+                    return Ok(None);
+                };
+                let (articles, next) = self
+                    .list_page(options, cursor.as_deref(), page, collected)
+                    .await?;
+                let collected = collected + articles.len();
+                let state = next.map(|cursor| (Some(cursor), page + 1, collected));
+                Ok(Some((articles, state)))
+            },
+        )
+        .map_ok(|articles| stream::iter(articles.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        let session = self.session().await?;
+        let record: PostRecord = self
+            .get(
+                &format!("fetch post {id}"),
+                "com.atproto.repo.getRecord",
+                &[
+                    ("repo", session.did.as_str()),
+                    ("collection", POST_COLLECTION),
+                    ("rkey", id),
+                ],
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(id.to_string()))?;
+        Ok(record.into_pulled_article(self.platform(), &session.handle))
+    }
+
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        // Post URLs look like https://bsky.app/profile/{handle}/post/{rkey}
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        match segments.as_slice() {
+            ["profile", _, "post", rkey] => self.fetch_article(rkey).await,
+            _ => Err(PullError::NotFound(url.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const DID: &str = "did:plc:abc123";
+
+    fn record(rkey: &str, text: &str, created_at: &str) -> Value {
+        json!({
+            "uri": format!("at://{DID}/app.bsky.feed.post/{rkey}"),
+            "cid": format!("cid-{rkey}"),
+            "value": {
+                "$type": "app.bsky.feed.post",
+                "text": text,
+                "createdAt": created_at,
+            },
+        })
+    }
+
+    async fn mount_session(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.server.createSession"))
+            .and(body_partial_json(json!({
+                "identifier": "alice.bsky.social",
+                "password": "app-password",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "accessJwt": "access-token",
+                "refreshJwt": "refresh-token",
+                "did": DID,
+                "handle": "alice.bsky.social",
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    fn puller(
+        server: &MockServer,
+    ) -> std::result::Result<BlueskyPuller, Box<dyn std::error::Error>> {
+        Ok(BlueskyPuller::new(
+            "alice.bsky.social".to_string(),
+            "app-password".to_string(),
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_service_url(&server.uri()))
+    }
+
+    #[test]
+    fn test_post_title() {
+        assert_eq!(
+            post_title("\n  Hello world \nmore text", "3k"),
+            "Hello world"
+        );
+        assert_eq!(post_title("", "3kabc"), "3kabc");
+        let long = "é".repeat(MAX_TITLE_CHARS + 5);
+        assert_eq!(
+            post_title(&long, "3k"),
+            format!("{}…", "é".repeat(MAX_TITLE_CHARS))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_follows_cursor() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_session(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/xrpc/com.atproto.repo.listRecords"))
+            .and(query_param("repo", DID))
+            .and(query_param("collection", POST_COLLECTION))
+            .and(query_param("cursor", "page-2"))
+            .and(header("authorization", "Bearer access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "records": [record("3k1", "Oldest", "2024-01-01T10:00:00.000Z")],
+            })))
+            .mount(&server)
+            .await;
+        let mut reply = record("3k3", "Replying", "2024-02-15T10:00:00.000Z");
+        reply["value"]["reply"] = json!({"root": {}, "parent": {}});
+        Mock::given(method("GET"))
+            .and(path("/xrpc/com.atproto.repo.listRecords"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "records": [
+                    record("3k4", "Newest", "2024-03-01T10:00:00.000Z"),
+                    reply,
+                    record("3k2", "Middle", "2024-02-01T10:00:00.000Z"),
+                ],
+                "cursor": "page-2",
+            })))
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+        let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["3k4", "3k2", "3k1"]);
+        assert_eq!(articles[0].title, "Newest");
+        assert_eq!(
+            articles[0].url.as_ref().map(Url::as_str),
+            Some("https://bsky.app/profile/alice.bsky.social/post/3k4")
+        );
+
+        let options = PullOptions {
+            since: Some("2024-01-15".parse()?),
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_maps_fields() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        mount_session(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/xrpc/com.atproto.repo.getRecord"))
+            .and(query_param("rkey", "3kabc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(record(
+                "3kabc",
+                "Shipping puller 1.0\n\nIt archives your posts.",
+                "2024-03-15T10:00:00.000Z",
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/xrpc/com.atproto.repo.getRecord"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "RecordNotFound",
+                "message": "Could not locate record",
+            })))
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let article = puller
+            .fetch_article_by_url(&Url::parse(
+                "https://bsky.app/profile/alice.bsky.social/post/3kabc",
+            )?)
+            .await?;
+        assert_eq!(article.platform_id, "3kabc");
+        assert_eq!(article.title, "Shipping puller 1.0");
+        assert_eq!(
+            article.body_markdown,
+            "Shipping puller 1.0\n\nIt archives your posts."
+        );
+        assert_eq!(article.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert!(article.tags.is_empty());
+        assert_eq!(article.author.as_deref(), Some("alice.bsky.social"));
+
+        assert!(matches!(
+            puller.fetch_article("missing").await,
+            Err(PullError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password_is_unauthorized(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.server.createSession"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+                "error": "AuthenticationRequired",
+                "message": "Invalid identifier or password",
+            })))
+            .mount(&server)
+            .await;
+
+        assert!(matches!(
+            puller(&server)?.verify_credentials().await,
+            Err(PullError::Unauthorized(_))
+        ));
+        Ok(())
+    }
+}
@@ -4,85 +4,288 @@
 //! including dev.to, vibe.forem.com, and custom Forem instances.
 
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, USER_AGENT};
+use futures::stream::BoxStream;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ETAG, IF_NONE_MATCH};
 use serde::Deserialize;
+use tracing::warn;
+use url::form_urlencoded::byte_serialize;
 use url::Url;
 
-use super::{ArticleMetadata, PullOptions, Puller};
-use crate::article::PulledArticle;
-use crate::error::{PullError, Result};
+use super::{
+    check_response, paginate, paginate_concurrent, send_with_retry, ArticleMetadata, HttpOptions,
+    ListPage, PullOptions, Puller, RateLimiter, RetryConfig,
+};
+use crate::article::{normalize_tags, url_slug, ArticleStats, Organization, PulledArticle};
+use crate::comments::Comment;
+use crate::dates::Since;
+use crate::error::{PullError, RequestContext, Result};
 use crate::forem::ForemInstance;
+use crate::html::fragment_to_markdown;
 use crate::platform::Platform;
 
-const PER_PAGE: u32 = 100;
+/// Forem API version requested unless configured otherwise.
+pub const DEFAULT_API_VERSION: &str = "v1";
 
-/// Article data from /articles/me/all endpoint (includes full content)
-#[derive(Debug, Deserialize, Clone)]
-struct ForemArticleListItem {
-    id: u64,
-    title: String,
-    body_markdown: String,
-    published_at: Option<DateTime<Utc>>,
-    url: String,
-    tag_list: Vec<String>,
-    canonical_url: Option<String>,
-    published: bool,
-}
+/// Articles requested per list page unless `with_per_page` says otherwise.
+const DEFAULT_PER_PAGE: u32 = 100;
 
-#[derive(Debug, Deserialize)]
+/// Largest page size the Forem API accepts.
+const MAX_PER_PAGE: u32 = 1000;
+
+/// With `PullOptions::skip_failed_pages` the listing still gives up once this
+/// many consecutive pages failed, since the instance is likely down altogether.
+const MAX_CONSECUTIVE_SKIPPED_PAGES: u32 = 3;
+
+/// Article from `/articles/{id}`, one of the `/articles/me/*` listings or the
+/// public `/articles?username=` listing.
+///
+/// The `me` listings send tags as a `tag_list` array. `/articles/{id}` sends a
+/// `tags` array next to `tag_list` joined into one string, and the public listing
+/// the other way round, so the two can't share a field through `#[serde(alias)]`
+/// without a duplicate field error; both accept either shape instead.
+#[derive(Debug, Deserialize, Clone)]
 struct ForemArticle {
     id: u64,
     title: String,
+    /// Left out of the public listing
+    #[serde(default)]
     body_markdown: String,
     published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    edited_at: Option<DateTime<Utc>>,
     url: String,
+    #[serde(default, deserialize_with = "deserialize_tag_list")]
     tags: Vec<String>,
-    #[serde(default)]
-    series: Option<ForemSeries>,
+    #[serde(default, deserialize_with = "deserialize_tag_list")]
+    tag_list: Vec<String>,
+    /// `None` when the payload left `series` out, `Some(None)` for an article in no series
+    #[allow(clippy::option_option)]
+    #[serde(default, deserialize_with = "deserialize_present")]
+    series: Option<Option<ForemSeries>>,
     canonical_url: Option<String>,
+    #[serde(default)]
+    cover_image: Option<String>,
+    #[serde(default)]
+    user: Option<ForemUser>,
+    #[serde(default)]
+    organization: Option<ForemOrganization>,
+    #[serde(flatten)]
+    stats: ForemStats,
     #[serde(default = "default_published")]
     published: bool,
+    /// `ETag` response header, filled in after deserializing
+    #[serde(skip)]
+    etag: Option<String>,
+}
+
+/// Tags as a list (`["rust", "cli"]`) or joined into one string (`"rust, cli"`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ForemTagList {
+    List(Vec<String>),
+    Joined(String),
+}
+
+fn deserialize_tag_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<ForemTagList>::deserialize(deserializer)? {
+        Some(ForemTagList::List(tags)) => tags,
+        Some(ForemTagList::Joined(tags)) => tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+/// Wraps a field that was in the payload in `Some`, so `null` and a missing key differ.
+fn deserialize_present<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Outcome of a request for a single article.
+enum SingleFetch {
+    Found(ForemArticle),
+    NotModified,
+    NotFound,
 }
 
 fn default_published() -> bool {
     true
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 struct ForemSeries {
     name: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+struct ForemUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct ForemOrganization {
+    name: String,
+    username: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+struct ForemStats {
+    #[serde(default)]
+    reading_time_minutes: Option<u32>,
+    #[serde(default)]
+    public_reactions_count: Option<u32>,
+    #[serde(default)]
+    comments_count: Option<u32>,
+}
+
+impl From<ForemStats> for ArticleStats {
+    fn from(stats: ForemStats) -> Self {
+        Self {
+            reading_time_minutes: stats.reading_time_minutes,
+            reactions_count: stats.public_reactions_count,
+            comments_count: stats.comments_count,
+        }
+    }
+}
+
+/// The authenticated user from `/users/me`.
+#[derive(Debug, Deserialize)]
+struct ForemMe {
+    username: String,
+}
+
+/// Comment from `/comments?a_id={id}`, with its replies nested in `children`.
+#[derive(Debug, Deserialize)]
+struct ForemComment {
+    id_code: String,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    body_html: String,
+    #[serde(default)]
+    user: Option<ForemUser>,
+    #[serde(default)]
+    deleted: bool,
+    #[serde(default)]
+    children: Vec<ForemComment>,
+}
+
+impl From<ForemComment> for Comment {
+    fn from(comment: ForemComment) -> Self {
+        Self {
+            id: comment.id_code,
+            author: if comment.deleted {
+                None
+            } else {
+                comment.user.map(|u| u.username)
+            },
+            created_at: comment.created_at,
+            body: if comment.deleted {
+                String::new()
+            } else {
+                fragment_to_markdown(&comment.body_html)
+            },
+            deleted: comment.deleted,
+            replies: comment.children.into_iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl From<ForemOrganization> for Organization {
+    fn from(org: ForemOrganization) -> Self {
+        Self {
+            name: org.name,
+            username: org.username,
+        }
+    }
+}
+
+/// Returns true when every dated article on a page was published before `since`.
+///
+/// The list endpoint returns articles newest first, so such a page means no later
+/// page can contain anything newer. Drafts have no `published_at` and never count
+/// towards the cutoff; a page holding only drafts does not end pagination.
+fn is_before_cutoff(articles: &[ForemArticle], since: Since) -> bool {
+    let mut dated = articles.iter().filter_map(|a| a.published_at).peekable();
+    dated.peek().is_some() && dated.all(|published_at| !since.admits(published_at))
+}
+
+/// Builds the `Accept` header requesting `version` of the API, e.g.
+/// `application/vnd.forem.api-v1+json` for `v1`.
+fn accept_header(version: &str) -> Result<HeaderValue> {
+    let is_version = !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.');
+    is_version
+        .then(|| HeaderValue::from_str(&format!("application/vnd.forem.api-{version}+json")).ok())
+        .flatten()
+        .ok_or_else(|| {
+            PullError::InvalidConfig(format!(
+                "Forem API version must be letters, digits and dots such as v1, got {version:?}"
+            ))
+        })
+}
+
 pub struct ForemPuller {
     instance: ForemInstance,
+    base_url: String,
     client: reqwest::Client,
     api_key: String,
-    /// Cache of articles fetched from list endpoint (for drafts that can't be fetched individually)
-    article_cache: RwLock<HashMap<String, ForemArticleListItem>>,
+    retry: RetryConfig,
+    limiter: RateLimiter,
+    per_page: u32,
+    /// Drafts seen by `list_articles`, kept because drafts can't be fetched individually.
+    ///
+    /// Published articles are not cached: their bodies are re-fetched on demand, so
+    /// memory is bounded by the number of drafts rather than the size of the account.
+    /// Only one page of bodies is held at a time while listing.
+    draft_cache: RwLock<HashMap<String, ForemArticle>>,
+    /// List pages skipped with `PullOptions::skip_failed_pages`
+    skipped_pages: Mutex<Vec<u32>>,
+    /// User whose public articles are listed instead of the key owner's, see `with_username`
+    username: Option<String>,
 }
 
 impl ForemPuller {
-    pub fn new(instance: ForemInstance, api_key: String) -> Result<Self> {
+    /// Creates a puller requesting `api_version` of the API (`DEFAULT_API_VERSION`
+    /// unless an instance needs another), e.g. `v1`.
+    pub fn new(
+        instance: ForemInstance,
+        api_key: String,
+        api_version: &str,
+        http: &HttpOptions,
+        retry: Option<RetryConfig>,
+    ) -> Result<Self> {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            ACCEPT,
-            HeaderValue::from_static("application/vnd.forem.api-v1+json"),
-        );
-        headers.insert(USER_AGENT, HeaderValue::from_static("puller/0.1.0"));
-
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
+        headers.insert(ACCEPT, accept_header(api_version)?);
+        let client = http.client(headers)?;
 
         Ok(Self {
+            base_url: instance.base_url(),
             instance,
             client,
             api_key,
-            article_cache: RwLock::new(HashMap::new()),
+            retry: retry.unwrap_or_default(),
+            limiter: http.rate_limiter()?,
+            per_page: DEFAULT_PER_PAGE,
+            draft_cache: RwLock::new(HashMap::new()),
+            skipped_pages: Mutex::new(Vec::new()),
+            username: None,
         })
     }
 
@@ -91,37 +294,326 @@ impl ForemPuller {
         &self.instance
     }
 
-    async fn fetch_page(&self, page: u32) -> Result<Vec<ForemArticleListItem>> {
-        let base_url = self.instance.base_url();
-        let url = format!("{base_url}/articles/me/all?page={page}&per_page={PER_PAGE}");
+    /// Sets how many articles each list page requests, clamped to 1..=1000.
+    #[must_use]
+    pub fn with_per_page(mut self, per_page: u32) -> Self {
+        self.per_page = per_page.clamp(1, MAX_PER_PAGE);
+        self
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .header("api-key", &self.api_key)
-            .send()
-            .await?;
+    /// Lists `username`'s published articles from the public `/articles?username=`
+    /// endpoint instead of the key owner's `me/all`. Drafts are never listed, and
+    /// no API key is needed.
+    #[must_use]
+    pub fn with_username(mut self, username: Option<String>) -> Self {
+        self.username = username;
+        self
+    }
+
+    /// Overrides the API base URL (used to point the puller at a mock server).
+    #[cfg(test)]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sends an authenticated GET request, retrying transient failures.
+    async fn get(&self, url: &str, etag: Option<&str>) -> Result<reqwest::Response> {
+        // The api-key travels in a header, which is never logged
+        send_with_retry(url, &self.retry, &self.limiter, || {
+            let request = self.client.get(url);
+            // Public listings with `with_username` may run without a key
+            let request = if self.api_key.is_empty() {
+                request
+            } else {
+                request.header("api-key", &self.api_key)
+            };
+            match etag {
+                Some(etag) => request.header(IF_NONE_MATCH, etag),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    /// Lists one page of `me/all`, keeping the articles that pass `options`.
+    ///
+    /// `collected` is the number of articles listed on earlier pages.
+    async fn list_page(
+        &self,
+        options: &PullOptions,
+        page: u32,
+        collected: usize,
+    ) -> Result<ListPage> {
+        if options.limit.is_some_and(|limit| collected >= limit) {
+            return Ok(ListPage::default());
+        }
 
-        if response.status() == 429 {
-            let retry_after = response
+        let fetched = self.fetch_list_page(page).await;
+        self.filter_page(options, page, fetched, collected)
+    }
+
+    /// Fetches one page of the article list.
+    async fn fetch_list_page(&self, page: u32) -> Result<Vec<ForemArticle>> {
+        let endpoint = match &self.username {
+            Some(username) => {
+                let username: String = byte_serialize(username.as_bytes()).collect();
+                format!("?username={username}")
+            }
+            None => "/me/all".to_string(),
+        };
+        self.fetch_page(&endpoint, page).await
+    }
+
+    /// Records a list page that still failed after retries as skipped when
+    /// `options.skip_failed_pages` allows it, or returns `error` to abort the listing.
+    ///
+    /// Pages come through here in page order, also when they were fetched
+    /// concurrently, so the consecutive failures counted are those of the listing.
+    fn skip_failed_page(&self, options: &PullOptions, page: u32, error: PullError) -> Result<()> {
+        // A bad key or a rate limit would fail every other page just the same
+        let skippable = matches!(error, PullError::Request { .. })
+            && !matches!(error.status(), Some(401 | 403 | 429));
+        if !options.skip_failed_pages || !skippable {
+            return Err(error);
+        }
+
+        let mut skipped = self
+            .skipped_pages
+            .lock()
+            .expect("skipped pages lock poisoned");
+        let gave_up = (1..MAX_CONSECUTIVE_SKIPPED_PAGES)
+            .all(|back| page > back && skipped.contains(&(page - back)));
+        if gave_up {
+            return Err(error);
+        }
+        warn!(page, %error, "skipping list page, the article list will be incomplete");
+        skipped.push(page);
+        Ok(())
+    }
+
+    /// Keeps the articles of a fetched `me/all` page that pass `options`, and
+    /// works out whether another page should be requested.
+    ///
+    /// A page that failed to download is handed to `skip_failed_page`; a skipped
+    /// page lists nothing, and the listing moves on to the next.
+    fn filter_page(
+        &self,
+        options: &PullOptions,
+        page: u32,
+        fetched: Result<Vec<ForemArticle>>,
+        collected: usize,
+    ) -> Result<ListPage> {
+        let reached_max_pages = options.max_pages.is_some_and(|max| page >= max);
+        let articles = match fetched {
+            Ok(articles) => articles,
+            Err(error) => {
+                self.skip_failed_page(options, page, error)?;
+                return Ok(ListPage {
+                    articles: Vec::new(),
+                    next_page: (!reached_max_pages).then_some(page + 1),
+                });
+            }
+        };
+
+        let limit_reached = |listed: usize| options.limit.is_some_and(|limit| listed >= limit);
+        let count = articles.len();
+        let reached_cutoff = options
+            .since
+            .is_some_and(|since| is_before_cutoff(&articles, since));
+
+        let mut listed = Vec::new();
+        for article in articles {
+            // The limit counts articles that passed the filters below
+            if limit_reached(collected + listed.len()) {
+                break;
+            }
+
+            // Filter by date if specified
+            if let Some(since) = options.since {
+                if let Some(published_at) = article.published_at {
+                    if !since.admits(published_at) {
+                        continue;
+                    }
+                }
+            }
+
+            // Filter drafts unless requested, or everything else with --drafts-only
+            if !article.published && !options.include_drafts {
+                continue;
+            }
+            if article.published && options.drafts_only {
+                continue;
+            }
+
+            let id_str = article.id.to_string();
+
+            let url = Url::parse(&article.url).ok();
+            listed.push(ArticleMetadata {
+                id: id_str.clone(),
+                platform: Platform::Forem(self.instance.clone()),
+                title: article.title.clone(),
+                published_at: article.published_at,
+                edited_at: article.edited_at,
+                slug: url.as_ref().and_then(url_slug),
+                url,
+                is_draft: !article.published,
+            });
+
+            // Cache drafts for later fetch (they can't be fetched via /articles/{id})
+            if !article.published {
+                let mut cache = self.draft_cache.write().expect("draft cache lock poisoned");
+                cache.insert(id_str, article);
+            }
+        }
+
+        let done = count < self.per_page as usize
+            || reached_cutoff
+            || reached_max_pages
+            || limit_reached(collected + listed.len());
+        Ok(ListPage {
+            articles: listed,
+            next_page: (!done).then_some(page + 1),
+        })
+    }
+
+    /// Fetches one page of a list endpoint: `endpoint` follows `/articles`, e.g.
+    /// `/me/all`, `/me/unpublished` or `?username=alice`.
+    async fn fetch_page(&self, endpoint: &str, page: u32) -> Result<Vec<ForemArticle>> {
+        let base_url = &self.base_url;
+        let per_page = self.per_page;
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        let url =
+            format!("{base_url}/articles{endpoint}{separator}page={page}&per_page={per_page}");
+
+        async {
+            let response = check_response(self.get(&url, None).await?).await?;
+            Ok(response.json().await?)
+        }
+        .await
+        .context(format!("list page {page}"), &url)
+    }
+
+    /// Fetches a single article from `/articles/{path}`.
+    ///
+    /// `path` is either a numeric id or `{username}/{slug}`. When `etag` is given it
+    /// is sent as `If-None-Match` so an unchanged article comes back as 304.
+    async fn fetch_single(&self, path: &str, etag: Option<&str>) -> Result<SingleFetch> {
+        let base_url = &self.base_url;
+        let url = format!("{base_url}/articles/{path}");
+
+        async {
+            let response = self.get(&url, etag).await?;
+
+            if response.status() == 304 {
+                return Ok(SingleFetch::NotModified);
+            }
+
+            if response.status() == 404 {
+                return Ok(SingleFetch::NotFound);
+            }
+            let response = check_response(response).await?;
+
+            let etag = response
                 .headers()
-                .get("retry-after")
+                .get(ETAG)
                 .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(60);
-            return Err(PullError::RateLimited(retry_after));
+                .map(str::to_owned);
+            let mut article: ForemArticle = response.json().await?;
+            article.etag = etag;
+            Ok(SingleFetch::Found(article))
         }
+        .await
+        .context(format!("fetch article {path}"), &url)
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(PullError::Api(format!(
-                "{} API returned {status}: {body}",
-                self.instance.display_name()
-            )));
+    /// Looks up one of the user's drafts through the authenticated unpublished listing.
+    ///
+    /// `/articles/{id}` only serves published articles, so a 404 there may still be
+    /// a draft owned by the API key's user.
+    async fn find_unpublished(&self, id: &str) -> Result<Option<ForemArticle>> {
+        let mut page = 1;
+
+        loop {
+            let articles = self.fetch_page("/me/unpublished", page).await?;
+            let count = articles.len();
+
+            if let Some(article) = articles.into_iter().find(|a| a.id.to_string() == id) {
+                return Ok(Some(article));
+            }
+
+            if count < self.per_page as usize {
+                return Ok(None);
+            }
+            page += 1;
+        }
+    }
+
+    /// Fills in the series of a cached draft whose listing payload left it out.
+    ///
+    /// Makes a single authenticated request and remembers the answer in the cache, so
+    /// the draft is never fetched twice. Failing that, the draft is kept without a series.
+    async fn backfill_series(&self, article: &mut ForemArticle) {
+        let id = article.id.to_string();
+        let series = match self.fetch_single(&id, None).await {
+            Ok(SingleFetch::Found(fetched)) => fetched.series.flatten(),
+            Ok(SingleFetch::NotModified | SingleFetch::NotFound) => None,
+            Err(e) => {
+                warn!("Could not look up the series of draft {id}: {e}");
+                None
+            }
+        };
+        article.series = Some(series);
+
+        let mut cache = self.draft_cache.write().expect("draft cache lock poisoned");
+        if let Some(cached) = cache.get_mut(&id) {
+            cached.series.clone_from(&article.series);
+        }
+    }
+
+    /// Fetches one page of top-level comments (with their replies) for an article.
+    async fn fetch_comment_page(&self, article_id: &str, page: u32) -> Result<Vec<ForemComment>> {
+        let base_url = &self.base_url;
+        let url = format!("{base_url}/comments?a_id={article_id}&page={page}");
+
+        async {
+            let response = check_response(self.get(&url, None).await?).await?;
+            Ok(response.json().await?)
         }
+        .await
+        .context(
+            format!("fetch comments page {page} of article {article_id}"),
+            &url,
+        )
+    }
+}
+
+impl ForemArticle {
+    fn into_pulled_article(self, platform: Platform) -> PulledArticle {
+        let tags = if self.tags.is_empty() {
+            self.tag_list
+        } else {
+            self.tags
+        };
 
-        Ok(response.json().await?)
+        PulledArticle {
+            platform_id: self.id.to_string(),
+            platform,
+            title: self.title,
+            body_markdown: self.body_markdown,
+            published_at: self.published_at,
+            edited_at: self.edited_at,
+            url: Url::parse(&self.url).ok(),
+            tags: normalize_tags(tags),
+            series: self.series.flatten().map(|s| s.name),
+            canonical_url: self.canonical_url.and_then(|u| Url::parse(&u).ok()),
+            cover_image: self.cover_image.and_then(|u| Url::parse(&u).ok()),
+            author: self.user.map(|u| u.username),
+            organization: self.organization.map(Organization::from),
+            stats: self.stats.into(),
+            etag: self.etag,
+            is_draft: !self.published,
+        }
     }
 }
 
@@ -131,132 +623,1480 @@ impl Puller for ForemPuller {
         Platform::Forem(self.instance.clone())
     }
 
-    async fn list_articles(&self, options: &PullOptions) -> Result<Vec<ArticleMetadata>> {
-        let mut all_articles = Vec::new();
-        let mut page = 1;
+    async fn verify_credentials(&self) -> Result<String> {
+        let base_url = &self.base_url;
+        let response = self.get(&format!("{base_url}/users/me"), None).await?;
 
-        loop {
-            let articles = self.fetch_page(page).await?;
-            let count = articles.len();
+        if response.status() == 401 || response.status() == 403 {
+            return Err(PullError::Unauthorized(self.platform().to_string()));
+        }
 
-            for article in articles {
-                // Filter by date if specified
-                if let Some(since) = options.since {
-                    if let Some(published_at) = article.published_at {
-                        if published_at.date_naive() < since {
-                            continue;
-                        }
-                    }
-                }
+        let me: ForemMe = check_response(response).await?.json().await?;
+        Ok(me.username)
+    }
 
-                // Filter drafts unless requested
-                if !article.published && !options.include_drafts {
-                    continue;
-                }
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        if options.list_concurrency > 1 {
+            paginate_concurrent(
+                options.list_concurrency,
+                options.max_pages,
+                move |page| self.fetch_list_page(page),
+                move |page, articles, collected| {
+                    self.filter_page(options, page, articles, collected)
+                },
+            )
+        } else {
+            paginate(move |page, collected| self.list_page(options, page, collected))
+        }
+    }
 
-                let id_str = article.id.to_string();
+    fn skipped_list_pages(&self) -> Vec<u32> {
+        let mut pages = self
+            .skipped_pages
+            .lock()
+            .expect("skipped pages lock poisoned")
+            .clone();
+        pages.sort_unstable();
+        pages
+    }
 
-                // Cache article data for later fetch (needed for drafts)
-                {
-                    let mut cache = self
-                        .article_cache
-                        .write()
-                        .expect("article cache lock poisoned");
-                    cache.insert(id_str.clone(), article.clone());
-                }
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        self.fetch_article_if_modified(id, None)
+            .await?
+            .ok_or_else(|| PullError::Api {
+                status: 304,
+                message: format!(
+                    "{} answered an unconditional request with 304 Not Modified",
+                    self.instance.display_name()
+                ),
+            })
+    }
 
-                all_articles.push(ArticleMetadata {
-                    id: id_str,
-                    platform: Platform::Forem(self.instance.clone()),
-                    title: article.title,
-                    published_at: article.published_at,
-                    url: Url::parse(&article.url).ok(),
-                    is_draft: !article.published,
-                });
+    async fn fetch_article_if_modified(
+        &self,
+        id: &str,
+        etag: Option<&str>,
+    ) -> Result<Option<PulledArticle>> {
+        // Check cache first (needed for drafts which can't be fetched via public API)
+        let cached = self
+            .draft_cache
+            .read()
+            .expect("draft cache lock poisoned")
+            .get(id)
+            .cloned();
+        if let Some(mut article) = cached {
+            if article.series.is_none() {
+                self.backfill_series(&mut article).await;
             }
+            return Ok(Some(article.into_pulled_article(self.platform())));
+        }
 
-            if count < PER_PAGE as usize {
-                break;
+        // Fall back to the authenticated API, which also covers drafts on a cold cache
+        match self.fetch_single(id, etag).await? {
+            SingleFetch::Found(article) => Ok(Some(article.into_pulled_article(self.platform()))),
+            SingleFetch::NotModified => Ok(None),
+            // Someone else's drafts are never visible
+            SingleFetch::NotFound if self.username.is_some() => {
+                Err(PullError::NotFound(id.to_string()))
             }
-            page += 1;
+            SingleFetch::NotFound => self
+                .find_unpublished(id)
+                .await?
+                .map(|article| Some(article.into_pulled_article(self.platform())))
+                .ok_or_else(|| PullError::NotFound(id.to_string())),
         }
-
-        Ok(all_articles)
     }
 
-    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
-        // Check cache first (needed for drafts which can't be fetched via public API)
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        // The username and slug would otherwise be looked up on the wrong instance
+        if ForemInstance::from_url(url).as_ref() != Some(&self.instance) {
+            return Err(PullError::InvalidConfig(format!(
+                "{url} is not an article on {}",
+                self.instance.display_name()
+            )));
+        }
+
+        // Forem article URLs have the form https://{host}/{username}/{slug}
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let [username, slug] = segments.as_slice() else {
+            return Err(PullError::NotFound(url.to_string()));
+        };
+
+        match self
+            .fetch_single(&format!("{username}/{slug}"), None)
+            .await?
         {
-            let cache = self
-                .article_cache
-                .read()
-                .expect("article cache lock poisoned");
-            if let Some(article) = cache.get(id) {
-                return Ok(PulledArticle {
-                    platform_id: article.id.to_string(),
-                    platform: Platform::Forem(self.instance.clone()),
-                    title: article.title.clone(),
-                    body_markdown: article.body_markdown.clone(),
-                    published_at: article.published_at,
-                    url: Url::parse(&article.url).ok(),
-                    tags: article.tag_list.clone(),
-                    series: None, // Series not available in list endpoint
-                    canonical_url: article
-                        .canonical_url
-                        .as_ref()
-                        .and_then(|u| Url::parse(u).ok()),
-                    is_draft: !article.published,
-                });
+            SingleFetch::Found(article) => Ok(article.into_pulled_article(self.platform())),
+            SingleFetch::NotModified | SingleFetch::NotFound => {
+                Err(PullError::NotFound(url.to_string()))
             }
         }
+    }
+
+    async fn fetch_comments(&self, article_id: &str) -> Result<Vec<Comment>> {
+        let mut comments: Vec<Comment> = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let batch = self.fetch_comment_page(article_id, page).await?;
+
+            // Instances that don't paginate comments return the full tree for every
+            // page, so a page starting with an already-seen comment is the end too
+            let repeated = batch
+                .first()
+                .is_some_and(|first| comments.iter().any(|c| c.id == first.id_code));
+            if batch.is_empty() || repeated {
+                return Ok(comments);
+            }
+
+            comments.extend(batch.into_iter().map(Comment::from));
+            page += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn list_item(id: u64, published_at: Option<&str>) -> Value {
+        json!({
+            "id": id,
+            "title": format!("Article {id}"),
+            "body_markdown": "Body",
+            "published_at": published_at,
+            "url": format!("https://dev.to/user/article-{id}"),
+            "tag_list": ["rust"],
+            "canonical_url": null,
+            "published": published_at.is_some(),
+        })
+    }
+
+    fn full_page(first_id: u64, published_at: &str) -> Value {
+        let items: Vec<Value> = (first_id..first_id + u64::from(DEFAULT_PER_PAGE))
+            .map(|id| list_item(id, Some(published_at)))
+            .collect();
+        Value::Array(items)
+    }
+
+    async fn mount_page(server: &MockServer, page: u32, body: Value, expected_calls: u64) {
+        Mock::given(method("GET"))
+            .and(path("/articles/me/all"))
+            .and(query_param("page", page.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    fn puller(server: &MockServer) -> Result<ForemPuller> {
+        puller_with(server, &HttpOptions::default())
+    }
+
+    fn puller_with(server: &MockServer, http: &HttpOptions) -> Result<ForemPuller> {
+        Ok(ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            http,
+            None,
+        )?
+        .with_base_url(server.uri()))
+    }
+
+    #[tokio::test]
+    async fn test_list_stops_paginating_before_since(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-20T10:00:00Z"), 1).await;
+        mount_page(&server, 2, full_page(101, "2023-01-01T10:00:00Z"), 1).await;
+        mount_page(&server, 3, full_page(201, "2022-01-01T10:00:00Z"), 0).await;
+
+        let options = PullOptions {
+            since: Some(Since::Date(
+                NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("invalid date")?,
+            )),
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), DEFAULT_PER_PAGE as usize);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_caches_only_drafts() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        let page = json!([
+            list_item(1, Some("2024-03-20T10:00:00Z")),
+            list_item(2, None),
+        ]);
+        mount_page(&server, 1, page, 1).await;
+
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            since: None,
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        puller.list_articles(&options).await?;
+
+        let cache = puller.draft_cache.read().map_err(|e| e.to_string())?;
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key("2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_draft_with_cold_cache(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/42"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/articles/me/unpublished"))
+            .and(header("api-key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([list_item(42, None)])))
+            .mount(&server)
+            .await;
+
+        let article = puller(&server)?.fetch_article("42").await?;
+
+        assert_eq!(article.title, "Article 42");
+        assert!(article.is_draft);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_draft_keeps_series() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        let mut draft = list_item(2, None);
+        draft["series"] = json!({"name": "Rust CLI Series"});
+        let mut standalone = list_item(3, None);
+        standalone["series"] = Value::Null;
+        mount_page(&server, 1, json!([draft, standalone]), 1).await;
+        // Both drafts said whether they're in a series, so nothing is backfilled
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            since: None,
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        puller.list_articles(&options).await?;
+
+        let article = puller.fetch_article("2").await?;
+        assert!(article.is_draft);
+        assert_eq!(article.series.as_deref(), Some("Rust CLI Series"));
+        assert_eq!(puller.fetch_article("3").await?.series, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_draft_backfills_series_once(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        // list_item() leaves out `series` altogether
+        mount_page(&server, 1, json!([list_item(2, None)]), 1).await;
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .and(header("api-key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": 2,
+                "title": "Article 2",
+                "body_markdown": "Body",
+                "published_at": null,
+                "published": false,
+                "url": "https://dev.to/user/article-2",
+                "tags": ["rust"],
+                "series": {"name": "Rust CLI Series"},
+                "canonical_url": null,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            since: None,
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        puller.list_articles(&options).await?;
+
+        for _ in 0..2 {
+            let article = puller.fetch_article("2").await?;
+            assert!(article.is_draft);
+            assert_eq!(article.series.as_deref(), Some("Rust CLI Series"));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_missing_article_is_not_found(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/42"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/articles/me/unpublished"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+
+        let result = puller(&server)?.fetch_article("42").await;
+
+        assert!(matches!(result, Err(PullError::NotFound(id)) if id == "42"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_by_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/user/my-post-abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": 7,
+                "title": "My Post",
+                "body_markdown": "Body",
+                "published_at": "2024-03-15T10:00:00Z",
+                "url": "https://dev.to/user/my-post-abc",
+                "tags": ["rust"],
+                "series": {"name": "Rust CLI Series"},
+                "canonical_url": null,
+                "user": {"name": "Some User", "username": "user"},
+                "organization": {"name": "Acme Inc", "username": "acme", "slug": "acme"},
+                "reading_time_minutes": 3,
+                "public_reactions_count": 42,
+                "comments_count": 5,
+            })))
+            .mount(&server)
+            .await;
+
+        let url = Url::parse("https://dev.to/user/my-post-abc")?;
+        let article = puller(&server)?.fetch_article_by_url(&url).await?;
+
+        assert_eq!(article.platform_id, "7");
+        assert_eq!(article.series.as_deref(), Some("Rust CLI Series"));
+        assert_eq!(article.author.as_deref(), Some("user"));
+        assert_eq!(
+            article.stats,
+            ArticleStats {
+                reading_time_minutes: Some(3),
+                reactions_count: Some(42),
+                comments_count: Some(5),
+            }
+        );
+        assert_eq!(
+            article.organization,
+            Some(Organization {
+                name: "Acme Inc".to_string(),
+                username: "acme".to_string(),
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_by_url_not_found(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let puller = puller(&server)?;
 
-        // Fall back to API for published articles
-        let base_url = self.instance.base_url();
-        let url = format!("{base_url}/articles/{id}");
+        let missing = Url::parse("https://dev.to/user/missing-post")?;
+        let result = puller.fetch_article_by_url(&missing).await;
+        assert!(matches!(result, Err(PullError::NotFound(_))));
 
-        let response = self
-            .client
-            .get(&url)
-            .header("api-key", &self.api_key)
-            .send()
+        let profile = Url::parse("https://dev.to/user")?;
+        let result = puller.fetch_article_by_url(&profile).await;
+        assert!(matches!(result, Err(PullError::NotFound(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_by_url_rejects_other_instance(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let puller = puller(&server)?;
+
+        let other = Url::parse("https://vibe.forem.com/user/my-post-abc")?;
+        let result = puller.fetch_article_by_url(&other).await;
+        assert!(
+            matches!(result, Err(PullError::InvalidConfig(message)) if message.contains("vibe.forem.com"))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_records_etag() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/7"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "W/\"abc\"")
+                    .set_body_json(json!({
+                        "id": 7,
+                        "title": "My Post",
+                        "body_markdown": "Body",
+                        "published_at": "2024-03-15T10:00:00Z",
+                        "url": "https://dev.to/user/my-post-abc",
+                        "tags": [],
+                        "canonical_url": null,
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let article = puller(&server)?.fetch_article("7").await?;
+
+        assert_eq!(article.etag.as_deref(), Some("W/\"abc\""));
+        assert_eq!(article.author, None);
+        assert_eq!(article.organization, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_not_modified() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/7"))
+            .and(header("if-none-match", "W/\"abc\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = puller(&server)?
+            .fetch_article_if_modified("7", Some("W/\"abc\""))
             .await?;
 
-        if response.status() == 404 {
-            return Err(PullError::NotFound(id.to_string()));
+        assert!(result.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_retried_then_reported(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/7"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let http = HttpOptions {
+            timeout: Duration::from_millis(50),
+            ..HttpOptions::default()
+        };
+        let retry = RetryConfig {
+            max_retries: 1,
+            ..RetryConfig::default()
+        };
+        let result = ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            Some(retry),
+        )?
+        .with_base_url(server.uri())
+        .fetch_article("7")
+        .await;
+
+        let Err(PullError::Request {
+            operation, source, ..
+        }) = result
+        else {
+            return Err("expected a request error".into());
+        };
+        assert_eq!(operation, "fetch article 7");
+        assert!(matches!(*source, PullError::Http(e) if e.is_timeout()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_custom_retry_config() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/7"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let http = HttpOptions {
+            timeout: Duration::from_millis(50),
+            ..HttpOptions::default()
+        };
+        let retry = RetryConfig {
+            max_retries: 2,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(15),
+            jitter: true,
+        };
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            Some(retry),
+        )?
+        .with_base_url(server.uri());
+
+        // Two retries after the first timeout, with the configured delays
+        assert_eq!(puller.retry, retry);
+        assert!(puller.fetch_article("7").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy_with_basic_auth(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/articles/me/all"))
+            // base64("user:secret")
+            .and(header("proxy-authorization", "Basic dXNlcjpzZWNyZXQ="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&proxy)
+            .await;
+
+        let mut proxy_url = Url::parse(&proxy.uri())?;
+        proxy_url
+            .set_username("user")
+            .map_err(|()| "invalid proxy url")?;
+        proxy_url
+            .set_password(Some("secret"))
+            .map_err(|()| "invalid proxy url")?;
+        let http = HttpOptions {
+            proxy: Some(proxy_url),
+            ..HttpOptions::default()
+        };
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            None,
+        )?
+        .with_base_url("http://forem.invalid/api");
+
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+
+        assert!(articles.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sends_configured_user_agent(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("user-agent", "archive-bot/1.0 (me@example.com)"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = HttpOptions {
+            user_agent: "archive-bot/1.0 (me@example.com)".to_string(),
+            ..HttpOptions::default()
+        };
+        puller_with(&server, &http)?
+            .list_articles(&PullOptions::default())
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sends_configured_api_version(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("accept", "application/vnd.forem.api-v2+json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            "v2",
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_base_url(server.uri())
+        .list_articles(&PullOptions::default())
+        .await?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_api_version_is_rejected() {
+        for version in ["", "v1+json", "v 2", "v1\n"] {
+            let result = ForemPuller::new(
+                ForemInstance::DevTo,
+                "test-key".to_string(),
+                version,
+                &HttpOptions::default(),
+                None,
+            );
+            assert!(
+                matches!(result, Err(PullError::InvalidConfig(_))),
+                "{version:?} was accepted"
+            );
         }
+    }
 
-        if response.status() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(60);
-            return Err(PullError::RateLimited(retry_after));
+    #[test]
+    fn test_invalid_user_agent_is_rejected() {
+        let http = HttpOptions {
+            user_agent: "bad\nagent".to_string(),
+            ..HttpOptions::default()
+        };
+        let result = ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            None,
+        );
+        assert!(matches!(result, Err(PullError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_default_user_agent_uses_crate_version() {
+        assert_eq!(
+            HttpOptions::default().user_agent,
+            format!("puller/{}", env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_draft_only_page_does_not_stop_pagination(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        let drafts: Vec<Value> = (1..=u64::from(DEFAULT_PER_PAGE))
+            .map(|id| list_item(id, None))
+            .collect();
+        mount_page(&server, 1, Value::Array(drafts), 1).await;
+        mount_page(
+            &server,
+            2,
+            json!([list_item(101, Some("2024-03-20T10:00:00Z"))]),
+            1,
+        )
+        .await;
+
+        let options = PullOptions {
+            since: Some(Since::Date(
+                NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("invalid date")?,
+            )),
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), DEFAULT_PER_PAGE as usize + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_comments_follows_pages(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .and(query_param("a_id", "7"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id_code": "c1",
+                "created_at": "2024-03-15T10:30:00Z",
+                "body_html": "<p>Nice &amp; <a href=\"https://example.com\">useful</a></p>",
+                "user": {"name": "Alice", "username": "alice"},
+                "children": [{
+                    "id_code": "c2",
+                    "created_at": "2024-03-15T11:00:00Z",
+                    "body_html": "<p>[deleted]</p>",
+                    "deleted": true,
+                    "children": [],
+                }],
+            }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id_code": "c3",
+                "body_html": "<p>Second page</p>",
+                "user": {"name": "Bob", "username": "bob"},
+            }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .and(query_param("page", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let comments = puller(&server)?.fetch_comments("7").await?;
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author.as_deref(), Some("alice"));
+        assert_eq!(comments[0].body, "Nice & [useful](https://example.com)");
+        assert!(comments[0].replies[0].deleted);
+        assert_eq!(comments[0].replies[0].author, None);
+        assert_eq!(comments[1].body, "Second page");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_comments_stops_on_repeated_page(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id_code": "c1",
+                "body_html": "<p>Only comment</p>",
+            }])))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let comments = puller(&server)?.fetch_comments("7").await?;
+
+        assert_eq!(comments.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_error_masks_echoed_api_key(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(401)
+                    .set_body_string(r#"{"error": "unauthorized", "api-key": "test-key"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let error = puller(&server)?
+            .list_articles(&PullOptions::default())
+            .await
+            .expect_err("401 should fail");
+
+        assert!(matches!(
+            &error,
+            PullError::Request { operation, source, .. }
+                if operation == "list page 1" && matches!(**source, PullError::Api { status: 401, .. })
+        ));
+        assert!(error.to_string().starts_with("list page 1 failed"));
+        assert!(!error.to_string().contains("test-key"));
+        assert!(!format!("{error:?}").contains("test-key"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_error_body_is_structured(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/1"))
+            .respond_with(
+                ResponseTemplate::new(422)
+                    .set_body_json(json!({"error": "Invalid article id", "status": 422})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("upstream unavailable"))
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let error = puller
+            .fetch_article("1")
+            .await
+            .expect_err("422 should fail");
+        assert!(matches!(
+            &error,
+            PullError::Request { source, .. } if matches!(
+                &**source,
+                PullError::Api { status: 422, message } if message == "Invalid article id"
+            )
+        ));
+
+        let error = puller
+            .fetch_article("2")
+            .await
+            .expect_err("503 should fail");
+        assert!(matches!(
+            &error,
+            PullError::Request { source, .. } if matches!(
+                &**source,
+                PullError::Api { status: 503, message } if message == "upstream unavailable"
+            )
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_credentials() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/me"))
+            .and(header("api-key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "type_of": "user",
+                "id": 1,
+                "username": "alice",
+                "name": "Alice",
+            })))
+            .mount(&server)
+            .await;
+
+        assert_eq!(puller(&server)?.verify_credentials().await?, "alice");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_credentials_rejected(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users/me"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+                "error": "unauthorized",
+                "status": 401,
+            })))
+            .mount(&server)
+            .await;
+
+        let error = puller(&server)?
+            .verify_credentials()
+            .await
+            .expect_err("401 should fail");
+
+        assert!(matches!(&error, PullError::Unauthorized(platform) if platform == "devto"));
+        assert_eq!(error.to_string(), "Invalid or missing API key for devto");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_limit_smaller_than_available(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        let mut items = vec![list_item(1, None)];
+        let published = Some("2024-03-15T10:00:00Z");
+        items.extend((2..=u64::from(DEFAULT_PER_PAGE)).map(|id| list_item(id, published)));
+        mount_page(&server, 1, Value::Array(items), 1).await;
+        mount_page(&server, 2, full_page(101, "2024-03-01T10:00:00Z"), 0).await;
+
+        let options = PullOptions {
+            since: None,
+            include_drafts: false,
+            drafts_only: false,
+            limit: Some(5),
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        // The excluded draft doesn't count towards the limit
+        let ids: Vec<_> = articles.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3", "4", "5", "6"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_limit_larger_than_available(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_page(
+            &server,
+            2,
+            json!([list_item(101, Some("2024-03-01T10:00:00Z"))]),
+            1,
+        )
+        .await;
+
+        let options = PullOptions {
+            since: None,
+            include_drafts: true,
+            drafts_only: false,
+            limit: Some(500),
+            max_pages: None,
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), DEFAULT_PER_PAGE as usize + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_stops_at_max_pages() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_page(&server, 2, full_page(101, "2024-03-01T10:00:00Z"), 0).await;
+
+        let options = PullOptions {
+            since: None,
+            include_drafts: true,
+            drafts_only: false,
+            limit: None,
+            max_pages: Some(1),
+            list_concurrency: 1,
+            skip_failed_pages: false,
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), DEFAULT_PER_PAGE as usize);
+        Ok(())
+    }
+
+    async fn mount_failing_page(server: &MockServer, page: u32, status: u16) {
+        Mock::given(method("GET"))
+            .and(path("/articles/me/all"))
+            .and(query_param("page", page.to_string()))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_list_skips_failing_page() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 2).await;
+        mount_failing_page(&server, 2, 500).await;
+        mount_page(
+            &server,
+            3,
+            json!([list_item(201, Some("2024-02-01T10:00:00Z"))]),
+            1,
+        )
+        .await;
+
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            include_drafts: true,
+            ..PullOptions::default()
+        };
+        let error = puller
+            .list_articles(&options)
+            .await
+            .expect_err("a failing page aborts the listing by default");
+        assert!(error.to_string().starts_with("list page 2 failed"));
+
+        let options = PullOptions {
+            skip_failed_pages: true,
+            ..options
+        };
+        let articles = puller.list_articles(&options).await?;
+        assert_eq!(articles.len(), DEFAULT_PER_PAGE as usize + 1);
+        assert_eq!(puller.skipped_list_pages(), vec![2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_never_skips_unauthorized_page(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_failing_page(&server, 2, 401).await;
+
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            include_drafts: true,
+            skip_failed_pages: true,
+            ..PullOptions::default()
+        };
+        let error = puller
+            .list_articles(&options)
+            .await
+            .expect_err("a rejected key fails every page");
+        assert_eq!(error.status(), Some(401));
+        assert!(puller.skipped_list_pages().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_gives_up_after_consecutive_failures(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        for page in 2..=4 {
+            mount_failing_page(&server, page, 500).await;
         }
+        mount_page(&server, 5, json!([]), 0).await;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(PullError::Api(format!(
-                "{} API returned {status}: {body}",
-                self.instance.display_name()
-            )));
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            include_drafts: true,
+            skip_failed_pages: true,
+            ..PullOptions::default()
+        };
+        let error = puller
+            .list_articles(&options)
+            .await
+            .expect_err("the instance looks down");
+        assert!(error.to_string().starts_with("list page 4 failed"));
+        assert_eq!(puller.skipped_list_pages(), vec![2, 3]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_list_counts_failures_in_page_order(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        for page in [2, 3, 5, 6, 7] {
+            mount_failing_page(&server, page, 500).await;
         }
+        mount_page(&server, 4, full_page(301, "2024-02-01T10:00:00Z"), 1).await;
+        mount_page(&server, 8, json!([]), 0).await;
 
-        let article: ForemArticle = response.json().await?;
-
-        Ok(PulledArticle {
-            platform_id: article.id.to_string(),
-            platform: Platform::Forem(self.instance.clone()),
-            title: article.title,
-            body_markdown: article.body_markdown,
-            published_at: article.published_at,
-            url: Url::parse(&article.url).ok(),
-            tags: article.tags,
-            series: article.series.map(|s| s.name),
-            canonical_url: article.canonical_url.and_then(|u| Url::parse(&u).ok()),
-            is_draft: !article.published,
-        })
+        // Page 4 comes back between the failures, so only pages 5 to 7 make three
+        // in a row, whichever request of a batch completes first
+        let puller = puller(&server)?;
+        let options = PullOptions {
+            include_drafts: true,
+            skip_failed_pages: true,
+            list_concurrency: 3,
+            ..PullOptions::default()
+        };
+        let error = puller
+            .list_articles(&options)
+            .await
+            .expect_err("the instance looks down");
+        assert!(error.to_string().starts_with("list page 7 failed"));
+        assert_eq!(puller.skipped_list_pages(), vec![2, 3, 5, 6]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_pages_concurrently() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_page(&server, 2, full_page(101, "2024-03-01T10:00:00Z"), 1).await;
+        mount_page(
+            &server,
+            3,
+            json!([list_item(201, Some("2024-02-01T10:00:00Z"))]),
+            1,
+        )
+        .await;
+        // Requested speculatively alongside pages 2 and 3, then dropped
+        mount_page(&server, 4, json!([]), 1).await;
+        mount_page(&server, 5, json!([]), 0).await;
+
+        let options = PullOptions {
+            include_drafts: true,
+            list_concurrency: 3,
+            ..PullOptions::default()
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), 2 * DEFAULT_PER_PAGE as usize + 1);
+        let ids: Vec<u64> = articles
+            .iter()
+            .map(|article| article.id.parse())
+            .collect::<std::result::Result<_, _>>()?;
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_list_respects_max_pages(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_page(&server, 2, full_page(101, "2024-03-01T10:00:00Z"), 1).await;
+        mount_page(&server, 3, full_page(201, "2024-02-01T10:00:00Z"), 0).await;
+
+        let options = PullOptions {
+            include_drafts: true,
+            max_pages: Some(2),
+            list_concurrency: 4,
+            ..PullOptions::default()
+        };
+        let articles = puller(&server)?.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), 2 * DEFAULT_PER_PAGE as usize);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_applies_to_requests(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": 7,
+                "title": "My Post",
+                "body_markdown": "Body",
+                "url": "https://dev.to/user/my-post-abc",
+                "tags": [],
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let http = HttpOptions {
+            rate_limit: Some(4.0),
+            ..HttpOptions::default()
+        };
+        let puller = puller_with(&server, &http)?;
+
+        // The spacing itself is covered by `test_rate_limiter_spaces_requests`
+        assert_eq!(puller.limiter.interval, Some(Duration::from_millis(250)));
+        puller.fetch_article("7").await?;
+        puller.fetch_article("7").await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_draft_filters() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        let items = json!([
+            list_item(1, None),
+            list_item(2, Some("2024-03-15T10:00:00Z")),
+            list_item(3, None),
+        ]);
+        mount_page(&server, 1, items, 3).await;
+        let puller = puller(&server)?;
+
+        let ids = |articles: Vec<ArticleMetadata>| -> Vec<String> {
+            articles.into_iter().map(|a| a.id).collect()
+        };
+        let with = |include_drafts, drafts_only| PullOptions {
+            include_drafts,
+            drafts_only,
+            ..PullOptions::default()
+        };
+
+        assert_eq!(
+            ids(puller.list_articles(&with(true, false)).await?),
+            vec!["1", "2", "3"]
+        );
+        assert_eq!(
+            ids(puller.list_articles(&with(false, false)).await?),
+            vec!["2"]
+        );
+        assert_eq!(
+            ids(puller.list_articles(&with(true, true)).await?),
+            vec!["1", "3"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_stream_fetches_pages_lazily(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use futures::{StreamExt, TryStreamExt};
+
+        let server = MockServer::start().await;
+        mount_page(&server, 1, full_page(1, "2024-03-15T10:00:00Z"), 1).await;
+        mount_page(&server, 2, full_page(101, "2024-03-01T10:00:00Z"), 0).await;
+
+        // Page 2 is only requested once everything on page 1 has been consumed
+        let puller = puller(&server)?;
+        let options = PullOptions::default();
+        let first_page: Vec<_> = puller
+            .list_articles_stream(&options)
+            .take(DEFAULT_PER_PAGE as usize)
+            .try_collect()
+            .await?;
+
+        assert_eq!(first_page.len(), DEFAULT_PER_PAGE as usize);
+        assert_eq!(first_page[0].id, "1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_with_small_pages() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        for (page, items) in [
+            (
+                1,
+                json!([
+                    list_item(1, Some("2024-03-15T10:00:00Z")),
+                    list_item(2, None)
+                ]),
+            ),
+            (2, json!([list_item(3, Some("2024-03-01T10:00:00Z"))])),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/articles/me/all"))
+                .and(query_param("page", page.to_string()))
+                .and(query_param("per_page", "2"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(items))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let puller = puller(&server)?.with_per_page(2);
+        let options = PullOptions {
+            include_drafts: true,
+            ..PullOptions::default()
+        };
+        let ids: Vec<_> = puller
+            .list_articles(&options)
+            .await?
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+
+        // The short second page ends the listing
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_page_is_clamped() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let http = HttpOptions::default();
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            "key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            None,
+        )?;
+        assert_eq!(puller.with_per_page(5000).per_page, MAX_PER_PAGE);
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            "key".to_string(),
+            DEFAULT_API_VERSION,
+            &http,
+            None,
+        )?;
+        assert_eq!(puller.with_per_page(0).per_page, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_article_from_both_endpoints() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        // Trimmed from /articles/me/all
+        let listed: ForemArticle = serde_json::from_value(json!({
+            "type_of": "article",
+            "id": 101,
+            "title": "Draft in a series",
+            "body_markdown": "Body",
+            "published": false,
+            "published_at": null,
+            "url": "https://dev.to/user/draft-in-a-series-temp-slug-1",
+            "tag_list": ["rust", "cli"],
+            "series": {"name": "Rust CLI Series"},
+            "canonical_url": null,
+            "cover_image": null,
+            "user": {"name": "Some User", "username": "user"},
+            "reading_time_minutes": 2,
+            "public_reactions_count": 0,
+            "comments_count": 0,
+        }))?;
+        let listed = listed.into_pulled_article(Platform::Forem(ForemInstance::DevTo));
+        assert_eq!(listed.tags, vec!["rust", "cli"]);
+        assert_eq!(listed.series.as_deref(), Some("Rust CLI Series"));
+        assert!(listed.is_draft);
+
+        // Trimmed from /articles/{id}, which sends tags both ways
+        let fetched: ForemArticle = serde_json::from_value(json!({
+            "type_of": "article",
+            "id": 102,
+            "title": "Published",
+            "body_markdown": "Body",
+            "published_at": "2024-03-15T10:00:00Z",
+            "edited_at": "2024-03-16T10:00:00Z",
+            "url": "https://dev.to/user/published-abc",
+            "tag_list": "rust, cli",
+            "tags": ["rust", "cli"],
+            "series": {"name": "Rust CLI Series"},
+            "canonical_url": "https://example.com/published",
+            "cover_image": "https://example.com/cover.png",
+            "user": {"name": "Some User", "username": "user"},
+            "reading_time_minutes": 3,
+            "public_reactions_count": 42,
+            "comments_count": 5,
+        }))?;
+        let fetched = fetched.into_pulled_article(Platform::Forem(ForemInstance::DevTo));
+        assert_eq!(fetched.tags, vec!["rust", "cli"]);
+        assert_eq!(fetched.series.as_deref(), Some("Rust CLI Series"));
+        assert!(!fetched.is_draft);
+        assert_eq!(fetched.stats.reactions_count, Some(42));
+
+        // Only the joined string is enough too
+        let joined: ForemArticle = serde_json::from_value(json!({
+            "id": 103,
+            "title": "Joined",
+            "body_markdown": "Body",
+            "published_at": "2024-03-15T10:00:00Z",
+            "url": "https://dev.to/user/joined-abc",
+            "tag_list": "rust, cli",
+            "canonical_url": null,
+        }))?;
+        assert_eq!(joined.tag_list, vec!["rust", "cli"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_public_articles_of_user(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        // Trimmed from /articles?username=, which leaves out the body
+        Mock::given(method("GET"))
+            .and(path("/articles"))
+            .and(query_param("username", "alice"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "type_of": "article",
+                    "id": 7,
+                    "title": "Alice's Post",
+                    "published_at": "2024-03-15T10:00:00Z",
+                    "url": "https://dev.to/alice/alices-post-abc",
+                    "tag_list": ["rust", "cli"],
+                    "tags": "rust, cli",
+                    "canonical_url": "https://dev.to/alice/alices-post-abc",
+                    "user": {"name": "Alice", "username": "alice"},
+                    "reading_time_minutes": 3,
+                    "public_reactions_count": 42,
+                    "comments_count": 5,
+                }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/articles/me/all"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            String::new(),
+            DEFAULT_API_VERSION,
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_base_url(server.uri())
+        .with_username(Some("alice".to_string()));
+        let options = PullOptions {
+            include_drafts: true,
+            ..PullOptions::default()
+        };
+        let articles = puller.list_articles(&options).await?;
+
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].id, "7");
+        assert_eq!(articles[0].title, "Alice's Post");
+        assert_eq!(articles[0].slug.as_deref(), Some("alices-post-abc"));
+        assert!(!articles[0].is_draft);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_missing_article_of_user_skips_drafts(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/42"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/articles/me/unpublished"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let puller = ForemPuller::new(
+            ForemInstance::DevTo,
+            String::new(),
+            DEFAULT_API_VERSION,
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_base_url(server.uri())
+        .with_username(Some("alice".to_string()));
+        let result = puller.fetch_article("42").await;
+
+        assert!(matches!(result, Err(PullError::NotFound(id)) if id == "42"));
+        Ok(())
     }
 }
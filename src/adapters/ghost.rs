@@ -0,0 +1,434 @@
+//! Puller for Ghost sites, using the public Content API.
+//!
+//! The Content API only serves published posts, as rendered HTML; the HTML is
+//! converted to markdown. It is authenticated with a Content API key sent as the
+//! `key` query parameter.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{
+    check_response, paginate, send_with_retry, ArticleMetadata, HttpOptions, ListPage, PullOptions,
+    Puller, RateLimiter, RetryConfig,
+};
+use crate::article::{url_slug, ArticleStats, PulledArticle};
+use crate::error::{PullError, RequestContext, Result};
+use crate::platform::Platform;
+
+/// Posts per page; the most the Content API returns at once.
+const PER_PAGE: u32 = 100;
+
+/// Content API version the response structs are written against.
+const ACCEPT_VERSION: &str = "v5.0";
+
+#[derive(Debug, Deserialize)]
+struct GhostPosts {
+    posts: Vec<GhostPost>,
+    #[serde(default)]
+    meta: Option<GhostMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostMeta {
+    pagination: GhostPagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostPagination {
+    next: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostPost {
+    id: String,
+    title: String,
+    #[serde(default)]
+    html: Option<String>,
+    feature_image: Option<Url>,
+    published_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    url: Option<Url>,
+    canonical_url: Option<Url>,
+    #[serde(default)]
+    tags: Vec<GhostTag>,
+    #[serde(default)]
+    primary_author: Option<GhostAuthor>,
+    #[serde(default)]
+    reading_time: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostTag {
+    name: String,
+    #[serde(default)]
+    visibility: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostAuthor {
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostSettings {
+    settings: GhostSite,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhostSite {
+    title: String,
+}
+
+impl GhostPost {
+    fn into_pulled_article(self, platform: Platform) -> PulledArticle {
+        PulledArticle {
+            platform_id: self.id,
+            platform,
+            title: self.title,
+            body_markdown: html2md::parse_html(&self.html.unwrap_or_default())
+                .trim()
+                .to_string(),
+            published_at: self.published_at,
+            edited_at: self.updated_at,
+            url: self.url,
+            // Internal tags (`#name`) organize the site and aren't shown to readers
+            tags: self
+                .tags
+                .into_iter()
+                .filter(|tag| tag.visibility.as_deref() != Some("internal"))
+                .map(|tag| tag.name)
+                .collect(),
+            series: None,
+            canonical_url: self.canonical_url,
+            cover_image: self.feature_image,
+            author: self.primary_author.map(|author| author.slug),
+            organization: None,
+            stats: ArticleStats {
+                reading_time_minutes: self.reading_time,
+                ..ArticleStats::default()
+            },
+            etag: None,
+            is_draft: false,
+        }
+    }
+}
+
+pub struct GhostPuller {
+    site_url: Url,
+    client: reqwest::Client,
+    content_key: String,
+    retry: RetryConfig,
+    limiter: RateLimiter,
+}
+
+impl GhostPuller {
+    pub fn new(
+        mut site_url: Url,
+        content_key: String,
+        http: &HttpOptions,
+        retry: Option<RetryConfig>,
+    ) -> Result<Self> {
+        // API paths are joined onto the site URL, which needs to end in a slash to
+        // keep a subdirectory install (https://example.com/blog) intact
+        if !site_url.path().ends_with('/') {
+            let path = format!("{}/", site_url.path());
+            site_url.set_path(&path);
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-version", HeaderValue::from_static(ACCEPT_VERSION));
+
+        Ok(Self {
+            site_url,
+            client: http.client(headers)?,
+            content_key,
+            retry: retry.unwrap_or_default(),
+            limiter: http.rate_limiter()?,
+        })
+    }
+
+    /// Requests a Content API endpoint, returning `None` on 404.
+    ///
+    /// `operation` describes the request in errors, e.g. "list page 3".
+    async fn get<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        let mut url = self
+            .site_url
+            .join(&format!("ghost/api/content/{endpoint}"))
+            .map_err(|e| PullError::InvalidConfig(format!("Invalid Ghost site URL: {e}")))?;
+        url.query_pairs_mut()
+            .append_pair("key", &self.content_key)
+            .extend_pairs(query);
+
+        async {
+            let response = send_with_retry(url.as_str(), &self.retry, &self.limiter, || {
+                self.client.get(url.clone())
+            })
+            .await?;
+
+            match response.status().as_u16() {
+                401 | 403 => return Err(PullError::Unauthorized(self.platform().to_string())),
+                404 => return Ok(None),
+                _ => {}
+            }
+            let response = check_response(response).await?;
+
+            Ok(Some(response.json().await?))
+        }
+        .await
+        .context(operation, url.as_str())
+    }
+
+    /// Fetches a single post from `posts/{path}`, which is `{id}/` or `slug/{slug}/`.
+    async fn fetch_post(&self, path: &str, not_found: &str) -> Result<PulledArticle> {
+        let posts: GhostPosts = self
+            .get(
+                &format!("fetch post {path}"),
+                &format!("posts/{path}"),
+                &[("include", "tags,authors")],
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(not_found.to_string()))?;
+
+        posts
+            .posts
+            .into_iter()
+            .next()
+            .map(|post| post.into_pulled_article(self.platform()))
+            .ok_or_else(|| PullError::NotFound(not_found.to_string()))
+    }
+
+    /// Lists one page of posts, newest first, keeping those that pass `options`.
+    ///
+    /// `collected` is the number of posts listed on earlier pages.
+    async fn list_page(
+        &self,
+        options: &PullOptions,
+        page: u32,
+        collected: usize,
+    ) -> Result<ListPage> {
+        if options.limit_reached(collected) {
+            return Ok(ListPage::default());
+        }
+
+        let posts: GhostPosts = self
+            .get(
+                &format!("list page {page}"),
+                "posts/",
+                &[
+                    ("limit", &PER_PAGE.to_string()),
+                    ("page", &page.to_string()),
+                    ("order", "published_at desc"),
+                    ("fields", "id,title,published_at,updated_at,url"),
+                ],
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(self.site_url.to_string()))?;
+
+        let next = posts.meta.and_then(|meta| meta.pagination.next);
+        let (posts, done) =
+            options.take_newest_first(page, collected, posts.posts, |post| post.published_at);
+        let articles = posts
+            .into_iter()
+            .map(|post| ArticleMetadata {
+                id: post.id,
+                platform: self.platform(),
+                title: post.title,
+                published_at: post.published_at,
+                edited_at: post.updated_at,
+                slug: post.url.as_ref().and_then(url_slug),
+                url: post.url,
+                is_draft: false,
+            })
+            .collect();
+        Ok(ListPage {
+            articles,
+            next_page: next.filter(|_| !done),
+        })
+    }
+}
+
+#[async_trait]
+impl Puller for GhostPuller {
+    fn platform(&self) -> Platform {
+        Platform::Ghost(self.site_url.clone())
+    }
+
+    /// Content API keys belong to an integration rather than a user, so the site
+    /// title is returned instead of a username.
+    async fn verify_credentials(&self) -> Result<String> {
+        let settings: GhostSettings = self
+            .get("read site settings", "settings/", &[])
+            .await?
+            .ok_or_else(|| PullError::NotFound(self.site_url.to_string()))?;
+        Ok(settings.settings.title)
+    }
+
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        // The Content API only serves published posts
+        if options.drafts_only {
+            return stream::empty().boxed();
+        }
+        paginate(move |page, collected| self.list_page(options, page, collected))
+    }
+
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        self.fetch_post(&format!("{id}/"), id).await
+    }
+
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        // Ghost post URLs end in the slug: https://{site}/{slug}/
+        let slug = url
+            .path_segments()
+            .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+            .ok_or_else(|| PullError::NotFound(url.to_string()))?;
+
+        self.fetch_post(&format!("slug/{slug}/"), url.as_str())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn post(id: &str, published_at: &str) -> Value {
+        json!({
+            "id": id,
+            "title": format!("Post {id}"),
+            "published_at": published_at,
+            "updated_at": published_at,
+            "url": format!("https://blog.example.com/post-{id}/"),
+        })
+    }
+
+    fn puller(server: &MockServer) -> std::result::Result<GhostPuller, Box<dyn std::error::Error>> {
+        Ok(GhostPuller::new(
+            Url::parse(&format!("{}/", server.uri()))?,
+            "content-key".to_string(),
+            &HttpOptions::default(),
+            None,
+        )?)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_maps_fields() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ghost/api/content/posts/abc123/"))
+            .and(query_param("key", "content-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "posts": [{
+                    "id": "abc123",
+                    "title": "Hello Ghost",
+                    "html": "<h2>Intro</h2><p>Some <em>text</em></p>",
+                    "feature_image": "https://blog.example.com/content/images/cover.png",
+                    "published_at": "2024-03-15T10:00:00.000+00:00",
+                    "updated_at": "2024-03-16T09:00:00.000+00:00",
+                    "url": "https://blog.example.com/hello-ghost/",
+                    "canonical_url": "https://original.example.com/hello",
+                    "reading_time": 4,
+                    "tags": [
+                        {"name": "Rust", "visibility": "public"},
+                        {"name": "#featured", "visibility": "internal"},
+                    ],
+                    "primary_author": {"slug": "alice", "name": "Alice"},
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let article = puller(&server)?.fetch_article("abc123").await?;
+        assert_eq!(article.title, "Hello Ghost");
+        assert!(article.body_markdown.contains("Intro"));
+        assert!(article.body_markdown.contains("*text*"));
+        assert_eq!(article.tags, vec!["Rust"]);
+        assert_eq!(
+            article.cover_image.as_ref().map(Url::as_str),
+            Some("https://blog.example.com/content/images/cover.png")
+        );
+        assert_eq!(
+            article.canonical_url.as_ref().map(Url::as_str),
+            Some("https://original.example.com/hello")
+        );
+        assert_eq!(article.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert_eq!(article.author.as_deref(), Some("alice"));
+        assert_eq!(article.stats.reading_time_minutes, Some(4));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_follows_pagination() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ghost/api/content/posts/"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "posts": [post("3", "2024-03-01T10:00:00Z"), post("2", "2024-02-01T10:00:00Z")],
+                "meta": {"pagination": {"page": 1, "next": 2}},
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ghost/api/content/posts/"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "posts": [post("1", "2024-01-01T10:00:00Z")],
+                "meta": {"pagination": {"page": 2, "next": null}},
+            })))
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+        let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2", "1"]);
+
+        let options = PullOptions {
+            since: Some("2024-01-15".parse()?),
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 2);
+
+        // A time on the cutoff day leaves out what was published earlier that day
+        let options = PullOptions {
+            since: Some("2024-02-01T12:00:00Z".parse()?),
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_is_unauthorized(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ghost/api/content/settings/"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        assert!(matches!(
+            puller(&server)?.verify_credentials().await,
+            Err(PullError::Unauthorized(_))
+        ));
+        Ok(())
+    }
+}
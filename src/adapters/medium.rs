@@ -0,0 +1,389 @@
+//! Puller for a Medium export archive.
+//!
+//! Medium has no API for reading articles, but "Download your information" in
+//! the account settings produces a zip whose `posts/` folder holds one HTML file
+//! per story. The unzipped archive is read once and both listing and fetching
+//! are served from it. Drafts are the files named `draft_*`, or any file in a
+//! `drafts/` folder next to the published ones.
+//!
+//! The export carries no tags; links marked `rel="tag"` are picked up in case a
+//! later export adds them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use tokio::sync::OnceCell;
+use url::Url;
+
+use super::{ArticleMetadata, PullOptions, Puller};
+use crate::article::{normalize_tags, ArticleStats, PulledArticle};
+use crate::error::{PullError, Result};
+use crate::html::decode_entities;
+use crate::platform::Platform;
+
+/// Folder of the export holding the stories.
+const POSTS_DIR: &str = "posts";
+/// Folder inside `posts/` treated as drafts, for archives sorted by hand.
+const DRAFTS_DIR: &str = "drafts";
+/// File name prefix Medium gives unpublished stories.
+const DRAFT_PREFIX: &str = "draft_";
+
+pub struct MediumArchivePuller {
+    export_dir: PathBuf,
+    /// The stories, read on first use and reused for every later call
+    posts: OnceCell<Vec<PulledArticle>>,
+}
+
+impl MediumArchivePuller {
+    pub fn new(export_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            export_dir: export_dir.into(),
+            posts: OnceCell::new(),
+        }
+    }
+
+    /// Returns the stories of the archive, reading them on first call.
+    async fn posts(&self) -> Result<&Vec<PulledArticle>> {
+        self.posts
+            .get_or_try_init(|| async { self.read_posts() })
+            .await
+    }
+
+    fn read_posts(&self) -> Result<Vec<PulledArticle>> {
+        let posts_dir = self.export_dir.join(POSTS_DIR);
+        if !posts_dir.is_dir() {
+            return Err(PullError::NotFound(format!(
+                "{} (expected an unzipped Medium export)",
+                posts_dir.display()
+            )));
+        }
+
+        let mut posts = read_dir(&posts_dir, false)?;
+        let drafts_dir = posts_dir.join(DRAFTS_DIR);
+        if drafts_dir.is_dir() {
+            posts.extend(read_dir(&drafts_dir, true)?);
+        }
+
+        // Newest first like the API-based platforms, drafts have no date and go last
+        posts.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        Ok(posts)
+    }
+}
+
+/// Parses every `.html` file directly inside `dir`.
+fn read_dir(dir: &Path, drafts: bool) -> Result<Vec<PulledArticle>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|path| {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("html"))
+    });
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            let html = fs::read_to_string(path)?;
+            Ok(parse_post(stem, &html, drafts))
+        })
+        .collect()
+}
+
+/// Builds an article from one exported story.
+///
+/// `stem` is the file name without extension, e.g.
+/// `2024-03-15_My-Post-1a2b3c4d5e6f`; Medium's post ID is its last segment.
+fn parse_post(stem: &str, html: &str, draft: bool) -> PulledArticle {
+    let is_draft = draft || stem.starts_with(DRAFT_PREFIX);
+    let id = stem.rsplit('-').next().unwrap_or(stem).to_string();
+
+    let title = between(html, r#"<h1 class="p-name">"#, "</h1>")
+        .or_else(|| between(html, "<title>", "</title>"))
+        .map(|title| decode_entities(title).trim().to_string())
+        .unwrap_or_default();
+
+    let body_html = between(
+        html,
+        r#"<section data-field="body" class="e-content">"#,
+        "<footer>",
+    )
+    .unwrap_or_default();
+
+    let published_at = if is_draft {
+        None
+    } else {
+        between(html, r#"<time class="dt-published" datetime=""#, "\"")
+            .and_then(|datetime| DateTime::parse_from_rfc3339(datetime).ok())
+            .map(|datetime| datetime.with_timezone(&Utc))
+    };
+
+    // Drafts link to a private preview, which is no use to anyone else
+    let url = if is_draft {
+        None
+    } else {
+        link_with_class(html, "p-canonical").and_then(|href| Url::parse(href).ok())
+    };
+
+    let author = link_with_class(html, "p-author h-card")
+        .and_then(|href| href.rsplit_once("/@"))
+        .map(|(_, username)| username.trim_end_matches('/').to_string());
+
+    PulledArticle {
+        platform_id: id,
+        platform: Platform::Medium,
+        body_markdown: strip_title_heading(&html2md::parse_html(body_html), &title),
+        title,
+        published_at,
+        edited_at: None,
+        url,
+        tags: normalize_tags(tag_links(html)),
+        series: None,
+        canonical_url: None,
+        cover_image: None,
+        author,
+        organization: None,
+        stats: ArticleStats::default(),
+        etag: None,
+        is_draft,
+    }
+}
+
+/// Returns the text between the first `start` and the `end` after it.
+fn between<'a>(html: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = html.find(start)? + start.len();
+    let len = html[from..].find(end)?;
+    Some(&html[from..from + len])
+}
+
+/// Returns the `href` of the first link with exactly this `class` attribute.
+fn link_with_class<'a>(html: &'a str, class: &str) -> Option<&'a str> {
+    let class_at = html.find(&format!(r#"class="{class}""#))?;
+    let tag_start = html[..class_at].rfind("<a ")?;
+    between(&html[tag_start..class_at], r#"href=""#, "\"")
+}
+
+/// Returns the text of every link marked `rel="tag"`.
+fn tag_links(html: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+    while let Some(rel_at) = rest.find(r#"rel="tag""#) {
+        rest = &rest[rel_at..];
+        if let Some(text) = between(rest, ">", "</a>") {
+            tags.push(decode_entities(text));
+        }
+        rest = &rest[1..];
+    }
+    tags
+}
+
+/// Medium repeats the title as the first heading of the body, which would show
+/// up twice under the frontmatter title.
+fn strip_title_heading(markdown: &str, title: &str) -> String {
+    let markdown = markdown.trim();
+    let body = markdown
+        .split_once('\n')
+        .filter(|(first, _)| first.trim_start_matches('#').trim() == title)
+        .map_or(markdown, |(_, body)| body);
+    body.trim().to_string()
+}
+
+fn to_metadata(article: &PulledArticle) -> ArticleMetadata {
+    ArticleMetadata {
+        id: article.platform_id.clone(),
+        platform: article.platform.clone(),
+        title: article.title.clone(),
+        published_at: article.published_at,
+        edited_at: article.edited_at,
+        url: article.url.clone(),
+        slug: article.url_slug(),
+        is_draft: article.is_draft,
+    }
+}
+
+#[async_trait]
+impl Puller for MediumArchivePuller {
+    fn platform(&self) -> Platform {
+        Platform::Medium
+    }
+
+    /// There are no credentials, so this only checks that the archive can be
+    /// read. The author of the stories stands in for the username.
+    async fn verify_credentials(&self) -> Result<String> {
+        let posts = self.posts().await?;
+        Ok(posts
+            .iter()
+            .find_map(|post| post.author.clone())
+            .unwrap_or_else(|| self.export_dir.display().to_string()))
+    }
+
+    /// The archive is read in one go, so it is listed as a single page.
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        stream::once(self.posts())
+            .map_ok(move |posts| {
+                let articles: Vec<_> = posts
+                    .iter()
+                    .filter(|post| {
+                        if post.is_draft {
+                            options.include_drafts || options.drafts_only
+                        } else {
+                            !options.drafts_only
+                        }
+                    })
+                    .filter(|post| {
+                        options.since.is_none_or(|since| {
+                            post.published_at
+                                .is_none_or(|published_at| since.admits(published_at))
+                        })
+                    })
+                    .take(options.limit.unwrap_or(usize::MAX))
+                    .map(to_metadata)
+                    .collect();
+                stream::iter(articles.into_iter().map(Ok))
+            })
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        let posts = self.posts().await?;
+        posts
+            .iter()
+            .find(|post| post.platform_id == id)
+            .cloned()
+            .ok_or_else(|| PullError::NotFound(id.to_string()))
+    }
+
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        let posts = self.posts().await?;
+        posts
+            .iter()
+            .find(|post| post.url.as_ref() == Some(url))
+            .cloned()
+            .ok_or_else(|| PullError::NotFound(url.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLISHED: &str = r#"<!DOCTYPE html><html><head><meta http-equiv="Content-Type" content="text/html; charset=utf-8"><title>Tips &amp; Tricks</title></head><body><article class="h-entry">
+<header>
+<h1 class="p-name">Tips &amp; Tricks</h1>
+</header>
+<section data-field="subtitle" class="p-summary">A few things I learned</section>
+<section data-field="body" class="e-content"><section name="a1b2" class="section section--body"><div class="section-content"><div class="section-inner sectionLayout--insetColumn"><h3 name="c3d4" class="graf graf--h3 graf--leading graf--title">Tips &amp; Tricks</h3><p name="e5f6" class="graf graf--p">Hello <strong class="markup--strong markup--p-strong">world</strong></p></div></div></section></section>
+<footer><p>By <a href="https://medium.com/@alice" class="p-author h-card">Alice</a> on <a href="https://medium.com/p/1a2b3c4d5e6f"><time class="dt-published" datetime="2024-03-15T10:00:00.000Z">March 15, 2024</time></a>.</p><p><a href="https://medium.com/@alice/tips-tricks-1a2b3c4d5e6f" class="p-canonical">Canonical link</a></p><p>Exported from <a href="https://medium.com">Medium</a> on April 1, 2024.</p></footer></article></body></html>"#;
+
+    const DRAFT: &str = r#"<!DOCTYPE html><html><head><title>Half Done</title></head><body><article class="h-entry">
+<header>
+<h1 class="p-name">Half Done</h1>
+</header>
+<section data-field="body" class="e-content"><p>Not yet</p></section>
+<footer><p><a href="https://medium.com/p/9f8e7d6c5b4a" class="p-canonical">Canonical link</a></p></footer></article></body></html>"#;
+
+    fn export() -> std::result::Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let posts = dir.path().join(POSTS_DIR);
+        fs::create_dir_all(posts.join(DRAFTS_DIR))?;
+        fs::write(
+            posts.join("2024-03-15_Tips---Tricks-1a2b3c4d5e6f.html"),
+            PUBLISHED,
+        )?;
+        fs::write(posts.join("draft_Half-Done-9f8e7d6c5b4a.html"), DRAFT)?;
+        fs::write(
+            posts
+                .join(DRAFTS_DIR)
+                .join("Sorted-By-Hand-0a0b0c0d0e0f.html"),
+            DRAFT,
+        )?;
+        fs::write(posts.join("notes.txt"), "not a post")?;
+        Ok(dir)
+    }
+
+    #[tokio::test]
+    async fn test_list_and_fetch() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = export()?;
+        let puller = MediumArchivePuller::new(dir.path());
+
+        assert_eq!(puller.verify_credentials().await?, "alice");
+
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].id, "1a2b3c4d5e6f");
+        assert_eq!(articles[0].platform_id(), "medium:1a2b3c4d5e6f");
+
+        let article = puller.fetch_article("1a2b3c4d5e6f").await?;
+        assert_eq!(article.title, "Tips & Tricks");
+        assert_eq!(article.body_markdown, "Hello **world**");
+        assert_eq!(article.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert_eq!(article.author.as_deref(), Some("alice"));
+        let url: Url = "https://medium.com/@alice/tips-tricks-1a2b3c4d5e6f".parse()?;
+        assert_eq!(article.url.as_ref(), Some(&url));
+        assert_eq!(
+            puller.fetch_article_by_url(&url).await?.title,
+            "Tips & Tricks"
+        );
+        assert!(!article.is_draft);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drafts() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = export()?;
+        let puller = MediumArchivePuller::new(dir.path());
+
+        let options = PullOptions {
+            include_drafts: true,
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 3);
+
+        let options = PullOptions {
+            drafts_only: true,
+            ..PullOptions::default()
+        };
+        let drafts = puller.list_articles(&options).await?;
+        let mut ids: Vec<_> = drafts.iter().map(|draft| draft.id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["0a0b0c0d0e0f", "9f8e7d6c5b4a"]);
+
+        let draft = puller.fetch_article("9f8e7d6c5b4a").await?;
+        assert!(draft.is_draft);
+        assert_eq!(draft.title, "Half Done");
+        assert_eq!(draft.body_markdown, "Not yet");
+        assert_eq!(draft.published_at, None);
+        assert_eq!(draft.url, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_missing_posts_dir() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let puller = MediumArchivePuller::new(dir.path());
+        assert!(matches!(
+            puller.list_articles(&PullOptions::default()).await,
+            Err(PullError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_links() {
+        let html = r#"<a href="/tag/rust" rel="tag">Rust</a> <a rel="tag" href="/tag/cli">CLI &amp; Tools</a>"#;
+        assert_eq!(tag_links(html), ["Rust", "CLI & Tools"]);
+    }
+}
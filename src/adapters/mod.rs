@@ -1,38 +1,584 @@
+pub mod bluesky;
 pub mod forem;
+pub mod ghost;
+pub mod medium;
+pub mod rss;
+pub mod writefreely;
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Utc};
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{RequestBuilder, Response};
+use serde::{Serialize, Serializer};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
 use url::Url;
 
 use crate::article::PulledArticle;
-use crate::error::Result;
+use crate::comments::Comment;
+use crate::dates::Since;
+use crate::error::{PullError, Result};
 use crate::platform::Platform;
+use crate::redact::redact;
+
+/// User-Agent sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = concat!("puller/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP client settings shared by API-based pullers.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Connect and overall request timeout
+    pub timeout: Duration,
+    /// Proxy for all requests (may carry basic auth credentials). When unset the
+    /// standard `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` variables apply.
+    pub proxy: Option<Url>,
+    pub user_agent: String,
+    /// Maximum requests per second across everything a puller sends; unlimited when unset
+    pub rate_limit: Option<f64>,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            proxy: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            rate_limit: None,
+        }
+    }
+}
+
+impl HttpOptions {
+    /// Builds an HTTP client with these settings on top of the given default headers.
+    pub fn client(&self, mut headers: HeaderMap) -> Result<reqwest::Client> {
+        let user_agent = HeaderValue::from_str(&self.user_agent).map_err(|_| {
+            PullError::InvalidConfig(format!(
+                "User-Agent is not a valid header value: {:?}",
+                self.user_agent
+            ))
+        })?;
+        headers.insert(USER_AGENT, user_agent);
+
+        let mut builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .connect_timeout(self.timeout)
+            .timeout(self.timeout);
+        if let Some(proxy) = &self.proxy {
+            // An explicit proxy replaces the environment-derived ones
+            builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Builds the limiter a puller shares between all of its requests.
+    pub fn rate_limiter(&self) -> Result<RateLimiter> {
+        RateLimiter::new(self.rate_limit)
+    }
+}
+
+/// How timed-out and failed connections are retried, see `send_with_retry`.
+///
+/// Puller constructors take an `Option<RetryConfig>`, where `None` stands for
+/// `RetryConfig::default()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt; 0 gives up on the first failure
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each further retry
+    pub base_delay: Duration,
+    /// Longest delay before any one retry
+    pub max_delay: Duration,
+    /// Wait a random time between half and all of each delay, so requests that
+    /// failed together don't retry in lockstep
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the delay before retry number `attempt`, counting from 0.
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        // Each `RandomState` is seeded differently, which is random enough here
+        let random = u32::try_from(RandomState::new().hash_one(attempt) >> 32).unwrap_or(u32::MAX);
+        let fraction = f64::from(random) / f64::from(u32::MAX);
+        delay / 2 + (delay / 2).mul_f64(fraction)
+    }
+}
+
+/// Longest wait between two requests `RateLimiter` accepts, so slot arithmetic
+/// can never overflow an `Instant`.
+const MAX_RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Spaces requests evenly so no more than a fixed number start each second.
+///
+/// This is a token bucket holding a single token: one request may go out
+/// immediately, and every later one waits for its own slot. Slots are handed
+/// out under a lock, so concurrent requests queue up instead of bursting.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Time between two requests, or `None` when unlimited
+    interval: Option<Duration>,
+    /// Earliest moment the next request may start
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: Option<f64>) -> Result<Self> {
+        let interval = match requests_per_second {
+            None => None,
+            Some(rps) if rps.is_finite() && rps > 0.0 => {
+                // A tiny rate gives an interval longer than a `Duration` holds, or
+                // one so long the next slot could overflow
+                match Duration::try_from_secs_f64(1.0 / rps) {
+                    Ok(interval) if interval <= MAX_RATE_LIMIT_INTERVAL => Some(interval),
+                    _ => {
+                        return Err(PullError::InvalidConfig(format!(
+                            "Rate limit is too low, got {rps} requests per second \
+                             (at least one request a day is required)"
+                        )))
+                    }
+                }
+            }
+            Some(rps) => {
+                return Err(PullError::InvalidConfig(format!(
+                    "Rate limit must be a positive number of requests per second, got {rps}"
+                )))
+            }
+        };
+        Ok(Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Waits until the next request is allowed to start.
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Sends the request built by `request`, logging it under the redacted `url`.
+///
+/// Timeouts and connection failures are transient, so they are retried with
+/// exponential backoff as `retry` says before the error is returned. Every
+/// attempt, retries included, waits for its turn with `limiter`.
+async fn send_with_retry(
+    url: &str,
+    retry: &RetryConfig,
+    limiter: &RateLimiter,
+    request: impl Fn() -> RequestBuilder + Send,
+) -> Result<Response> {
+    let logged_url = redact(url);
+    let mut attempt = 0;
+
+    loop {
+        limiter.acquire().await;
+        debug!(url = %logged_url, attempt, "sending request");
+        match request().send().await {
+            Ok(response) => {
+                debug!(url = %logged_url, status = %response.status(), "received response");
+                return Ok(response);
+            }
+            Err(e) if (e.is_timeout() || e.is_connect()) && attempt < retry.max_retries => {
+                let delay = retry.delay(attempt);
+                debug!(url = %logged_url, attempt, ?delay, error = %e, "retrying request");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                debug!(url = %logged_url, attempt, error = %e, "giving up on request");
+                return Err(e.into());
+            }
+        }
+    }
+}
+
+/// Turns a 429 into `PullError::RateLimited` and any other error status into
+/// `PullError::Api`, passing successful responses through.
+///
+/// Statuses that mean something particular on a platform, such as 401 or 404,
+/// are for the caller to handle first.
+async fn check_response(response: Response) -> Result<Response> {
+    if response.status() == 429 {
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        return Err(PullError::RateLimited(retry_after));
+    }
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(PullError::api(status, &body));
+    }
+    Ok(response)
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct PullOptions {
-    pub since: Option<NaiveDate>,
+    pub since: Option<Since>,
     pub include_drafts: bool,
+    /// Keep only drafts, dropping every published article
+    pub drafts_only: bool,
+    /// Stop once this many articles passed the filters
+    pub limit: Option<usize>,
+    /// Stop paginating after this many list pages
+    pub max_pages: Option<u32>,
+    /// List pages requested at once on platforms that support it; 0 and 1 both
+    /// request one page at a time
+    pub list_concurrency: u32,
+    /// Skip list pages that still fail after retries instead of aborting the
+    /// listing, see `Puller::skipped_list_pages`
+    pub skip_failed_pages: bool,
 }
 
-#[derive(Debug, Clone)]
+impl PullOptions {
+    /// Whether `listed` articles already fill `limit`.
+    fn limit_reached(&self, listed: usize) -> bool {
+        self.limit.is_some_and(|limit| listed >= limit)
+    }
+
+    /// Keeps the items of a newest-first list page that fit `limit` and `since`,
+    /// returning them with whether the listing is done.
+    ///
+    /// `collected` is the number of articles listed on earlier pages. Being newest
+    /// first, the first item published before `since` ends the listing, as do
+    /// `limit` and `max_pages`. `published_at` gives an item's publish date.
+    fn take_newest_first<T>(
+        &self,
+        page: u32,
+        collected: usize,
+        items: Vec<T>,
+        published_at: impl Fn(&T) -> Option<DateTime<Utc>>,
+    ) -> (Vec<T>, bool) {
+        let mut kept = Vec::new();
+        let mut reached_cutoff = false;
+        for item in items {
+            if self.limit_reached(collected + kept.len()) {
+                break;
+            }
+            if let (Some(since), Some(published_at)) = (self.since, published_at(&item)) {
+                if !since.admits(published_at) {
+                    reached_cutoff = true;
+                    break;
+                }
+            }
+            kept.push(item);
+        }
+
+        let done = reached_cutoff
+            || self.max_pages.is_some_and(|max| page >= max)
+            || self.limit_reached(collected + kept.len());
+        (kept, done)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ArticleMetadata {
     pub id: String,
+    #[serde(serialize_with = "serialize_platform")]
     pub platform: Platform,
     pub title: String,
     pub published_at: Option<DateTime<Utc>>,
+    pub edited_at: Option<DateTime<Utc>>,
     pub url: Option<Url>,
+    /// Last segment of `url`, e.g. `my-post-1a2b`, to match listed articles with local files
+    pub slug: Option<String>,
     pub is_draft: bool,
 }
 
+/// Serializes a platform as its CLI string (e.g. `devto`, `forem:vibe`).
+fn serialize_platform<S: Serializer>(
+    platform: &Platform,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(platform)
+}
+
 impl ArticleMetadata {
     pub fn platform_id(&self) -> String {
         format!("{}:{}", self.platform, self.id)
     }
 }
 
+/// One page of an article listing, after the `PullOptions` filters.
+#[derive(Debug, Default)]
+pub struct ListPage {
+    pub articles: Vec<ArticleMetadata>,
+    /// Page to request next, or `None` once the listing is done
+    pub next_page: Option<u32>,
+}
+
+/// Streams the articles of a paginated listing, requesting a page only once the
+/// previous one has been consumed, so at most one page is held at a time.
+///
+/// `fetch` is called with the page number and how many articles were yielded
+/// before it, which is what `PullOptions::limit` counts against.
+fn paginate<'a, F, Fut>(fetch: F) -> BoxStream<'a, Result<ArticleMetadata>>
+where
+    F: Fn(u32, usize) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<ListPage>> + Send + 'a,
+{
+    stream::try_unfold((Some(1), 0), move |(page, collected)| {
+        let request = page.map(|page| fetch(page, collected));
+        async move {
+            let Some(request) = request else {
+                return Ok(None);
+            };
+            let page = request.await?;
+            let collected = collected + page.articles.len();
+            Ok(Some((page.articles, (page.next_page, collected))))
+        }
+    })
+    .map_ok(|articles| stream::iter(articles.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed()
+}
+
+/// Like `paginate`, but requests up to `concurrency` pages at once before it
+/// knows whether they exist, which speeds up listing large accounts.
+///
+/// `fetch` only downloads a page; `process` filters it into a `ListPage` with
+/// the page number and how many articles were yielded before it. A failed
+/// download is passed on to `process` too, which decides whether it ends the
+/// listing, so failures are handled in page order. Pages are processed in order, and the pages of a batch after the one that ends the
+/// listing are dropped unprocessed. The first page is requested alone, so an
+/// account with a single page doesn't send a batch of useless requests, and
+/// no batch reaches past `max_pages`.
+fn paginate_concurrent<'a, T, F, Fut, P>(
+    concurrency: u32,
+    max_pages: Option<u32>,
+    fetch: F,
+    process: P,
+) -> BoxStream<'a, Result<ArticleMetadata>>
+where
+    T: Send + 'a,
+    F: Fn(u32) -> Fut + Send + 'a,
+    Fut: Future<Output = T> + Send + 'a,
+    P: Fn(u32, T, usize) -> Result<ListPage> + Send + 'a,
+{
+    stream::try_unfold((Some(1), 0, process), move |(page, collected, process)| {
+        let batch = page.map(|first| {
+            let size = if first == 1 { 1 } else { concurrency.max(1) };
+            let last = first
+                .saturating_add(size - 1)
+                .min(max_pages.unwrap_or(u32::MAX))
+                .max(first);
+            let requests: Vec<_> = (first..=last).map(&fetch).collect();
+            (first, future::join_all(requests))
+        });
+        async move {
+            let Some((first, requests)) = batch else {
+                return Ok(None);
+            };
+            let mut collected = collected;
+            let mut articles = Vec::new();
+            let mut next_page = None;
+            for (page, fetched) in (first..).zip(requests.await) {
+                let listed = process(page, fetched, collected)?;
+                collected += listed.articles.len();
+                articles.extend(listed.articles);
+                next_page = listed.next_page;
+                if next_page.is_none() {
+                    break;
+                }
+            }
+            Ok(Some((articles, (next_page, collected, process))))
+        }
+    })
+    .map_ok(|articles| stream::iter(articles.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed()
+}
+
 #[async_trait]
 pub trait Puller: Send + Sync {
     fn platform(&self) -> Platform;
-    async fn list_articles(&self, options: &PullOptions) -> Result<Vec<ArticleMetadata>>;
+
+    /// Checks the configured credentials, returning the authenticated username.
+    ///
+    /// Fails with `PullError::Unauthorized` when the platform rejects the key.
+    async fn verify_credentials(&self) -> Result<String>;
+
+    /// Lists the articles matching `options` as their pages arrive, so a caller
+    /// can work through a large account without holding the whole list.
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>>;
+
+    /// List pages skipped so far because of `PullOptions::skip_failed_pages`.
+    ///
+    /// Platforms that can't skip a page always abort the listing instead.
+    fn skipped_list_pages(&self) -> Vec<u32> {
+        Vec::new()
+    }
+
+    /// Collects `list_articles_stream` into a single list.
+    async fn list_articles(&self, options: &PullOptions) -> Result<Vec<ArticleMetadata>> {
+        self.list_articles_stream(options).try_collect().await
+    }
+
     async fn fetch_article(&self, id: &str) -> Result<PulledArticle>;
+
+    /// Fetches an article unless it still matches `etag`, returning `None` if unchanged.
+    ///
+    /// Platforms without conditional requests ignore `etag` and always fetch.
+    async fn fetch_article_if_modified(
+        &self,
+        id: &str,
+        _etag: Option<&str>,
+    ) -> Result<Option<PulledArticle>> {
+        self.fetch_article(id).await.map(Some)
+    }
+
+    /// Fetches a single article from its public URL.
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        Err(PullError::UnsupportedPlatform(format!(
+            "{} does not support pulling by URL ({url})",
+            self.platform()
+        )))
+    }
+
+    /// Fetches the comment threads of an article, oldest first.
+    async fn fetch_comments(&self, _article_id: &str) -> Result<Vec<Comment>> {
+        Err(PullError::UnsupportedPlatform(format!(
+            "{} does not support pulling comments",
+            self.platform()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forem::ForemInstance;
+
+    #[test]
+    fn test_metadata_serializes_as_strings() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let meta = ArticleMetadata {
+            id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::Vibe),
+            title: "Test Article".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
+            url: Some("https://vibe.forem.com/user/test-article".parse()?),
+            slug: Some("test-article".to_string()),
+            is_draft: false,
+        };
+
+        let json = serde_json::to_value(&meta)?;
+        assert_eq!(json["platform"], "forem:vibe");
+        assert_eq!(json["published_at"], "2024-03-15T10:00:00Z");
+        assert_eq!(json["url"], "https://vibe.forem.com/user/test-article");
+        assert_eq!(json["slug"], "test-article");
+        assert_eq!(json["is_draft"], false);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_spaces_requests(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let limiter = RateLimiter::new(Some(4.0))?;
+
+        // The first request goes out at once, the next two wait 250ms each
+        let start = Instant::now();
+        tokio::join!(limiter.acquire(), limiter.acquire(), limiter.acquire());
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+
+        // Time spent elsewhere counts towards the wait
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter_rejects_non_positive_rates() {
+        assert!(RateLimiter::new(None).is_ok());
+        assert!(RateLimiter::new(Some(0.5)).is_ok());
+        assert!(RateLimiter::new(Some(1.0 / 3600.0)).is_ok());
+        for rps in [
+            0.0,
+            -1.0,
+            f64::NAN,
+            f64::INFINITY,
+            1e-6,
+            1e-20,
+            f64::MIN_POSITIVE,
+        ] {
+            assert!(matches!(
+                RateLimiter::new(Some(rps)),
+                Err(PullError::InvalidConfig(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max() {
+        let retry = RetryConfig {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            jitter: false,
+        };
+        let delays: Vec<_> = (0..5).map(|attempt| retry.delay(attempt)).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 500, 500]
+                .map(Duration::from_millis)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_retry_jitter_stays_within_half_the_delay() {
+        let retry = RetryConfig {
+            jitter: true,
+            ..RetryConfig::default()
+        };
+        for _ in 0..100 {
+            let delay = retry.delay(1);
+            assert!(delay >= Duration::from_millis(500));
+            assert!(delay <= Duration::from_secs(1));
+        }
+    }
 }
@@ -0,0 +1,361 @@
+//! Puller for any RSS or Atom feed.
+//!
+//! Feeds carry every entry's content inline, so the feed is downloaded once and
+//! both listing and fetching are served from it. Entry HTML is converted to
+//! markdown; feeds have no drafts, series or engagement numbers.
+
+use async_trait::async_trait;
+use feed_rs::model::{Entry, Feed};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT};
+use tokio::sync::OnceCell;
+use url::Url;
+
+use super::{
+    send_with_retry, ArticleMetadata, HttpOptions, PullOptions, Puller, RateLimiter, RetryConfig,
+};
+use crate::article::{ArticleStats, PulledArticle};
+use crate::error::{PullError, RequestContext, Result};
+use crate::platform::Platform;
+
+/// Accept header listing the feed formats we can parse, most specific first.
+const FEED_ACCEPT: &str =
+    "application/atom+xml, application/rss+xml, application/xml;q=0.9, text/xml;q=0.8, */*;q=0.5";
+
+/// A downloaded feed, reduced to what the puller needs.
+struct FeedContents {
+    title: Option<String>,
+    articles: Vec<PulledArticle>,
+}
+
+pub struct RssPuller {
+    feed_url: Url,
+    client: reqwest::Client,
+    retry: RetryConfig,
+    limiter: RateLimiter,
+    /// The feed, downloaded on first use and reused for every later call
+    feed: OnceCell<FeedContents>,
+}
+
+impl RssPuller {
+    pub fn new(feed_url: Url, http: &HttpOptions, retry: Option<RetryConfig>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(FEED_ACCEPT));
+
+        Ok(Self {
+            feed_url,
+            client: http.client(headers)?,
+            retry: retry.unwrap_or_default(),
+            limiter: http.rate_limiter()?,
+            feed: OnceCell::new(),
+        })
+    }
+
+    /// Returns the downloaded feed, fetching it on first call.
+    async fn feed(&self) -> Result<&FeedContents> {
+        self.feed.get_or_try_init(|| self.fetch_feed()).await
+    }
+
+    async fn fetch_feed(&self) -> Result<FeedContents> {
+        let url = self.feed_url.as_str();
+        let bytes = async {
+            let response =
+                send_with_retry(url, &self.retry, &self.limiter, || self.client.get(url)).await?;
+
+            if response.status() == 404 {
+                return Err(PullError::NotFound(url.to_string()));
+            }
+
+            if !response.status().is_success() {
+                return Err(PullError::Api {
+                    status: response.status().as_u16(),
+                    message: format!("Feed {url} could not be downloaded"),
+                });
+            }
+
+            Ok(response.bytes().await?)
+        }
+        .await
+        .context("fetch feed", url)?;
+        let feed = feed_rs::parser::parse(bytes.as_ref())?;
+        Ok(self.feed_contents(feed))
+    }
+
+    fn feed_contents(&self, feed: Feed) -> FeedContents {
+        FeedContents {
+            title: feed.title.map(|title| title.content),
+            articles: feed
+                .entries
+                .into_iter()
+                .map(|entry| self.to_pulled_article(entry))
+                .collect(),
+        }
+    }
+
+    fn to_pulled_article(&self, entry: Entry) -> PulledArticle {
+        // The alternate link points at the post itself; other rels are enclosures etc.
+        let url = entry
+            .links
+            .iter()
+            .find(|link| link.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+            .or_else(|| entry.links.first())
+            .and_then(|link| self.feed_url.join(&link.href).ok());
+
+        // Full content when the feed has it, otherwise the summary
+        let html = entry
+            .content
+            .and_then(|content| content.body)
+            .or_else(|| entry.summary.map(|summary| summary.content))
+            .unwrap_or_default();
+
+        PulledArticle {
+            platform_id: entry.id,
+            platform: self.platform(),
+            title: entry.title.map(|title| title.content).unwrap_or_default(),
+            body_markdown: html2md::parse_html(&html).trim().to_string(),
+            published_at: entry.published.or(entry.updated),
+            edited_at: entry.updated,
+            url,
+            tags: entry
+                .categories
+                .into_iter()
+                .map(|category| category.term)
+                .collect(),
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: entry.authors.into_iter().next().map(|person| person.name),
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        }
+    }
+}
+
+fn to_metadata(article: &PulledArticle) -> ArticleMetadata {
+    ArticleMetadata {
+        id: article.platform_id.clone(),
+        platform: article.platform.clone(),
+        title: article.title.clone(),
+        published_at: article.published_at,
+        edited_at: article.edited_at,
+        url: article.url.clone(),
+        slug: article.url_slug(),
+        is_draft: article.is_draft,
+    }
+}
+
+#[async_trait]
+impl Puller for RssPuller {
+    fn platform(&self) -> Platform {
+        Platform::Rss(self.feed_url.clone())
+    }
+
+    /// Feeds are public, so this only checks that the feed can be fetched and
+    /// parsed. The feed title stands in for the username.
+    async fn verify_credentials(&self) -> Result<String> {
+        let feed = self.feed().await?;
+        Ok(feed
+            .title
+            .clone()
+            .unwrap_or_else(|| self.feed_url.to_string()))
+    }
+
+    /// The whole feed arrives in one response, so it is listed as a single page.
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        // Feeds only carry published entries
+        if options.drafts_only {
+            return stream::empty().boxed();
+        }
+
+        stream::once(self.feed())
+            .map_ok(move |feed| {
+                let articles: Vec<_> = feed
+                    .articles
+                    .iter()
+                    .filter(|article| {
+                        options.since.is_none_or(|since| {
+                            article
+                                .published_at
+                                .is_none_or(|published_at| since.admits(published_at))
+                        })
+                    })
+                    .take(options.limit.unwrap_or(usize::MAX))
+                    .map(to_metadata)
+                    .collect();
+                stream::iter(articles.into_iter().map(Ok))
+            })
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        let feed = self.feed().await?;
+        feed.articles
+            .iter()
+            .find(|article| article.platform_id == id)
+            .cloned()
+            .ok_or_else(|| PullError::NotFound(id.to_string()))
+    }
+
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        let feed = self.feed().await?;
+        feed.articles
+            .iter()
+            .find(|article| article.url.as_ref() == Some(url))
+            .cloned()
+            .ok_or_else(|| PullError::NotFound(url.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Alice's Blog</title>
+    <link>https://blog.example.com/</link>
+    <item>
+      <guid>https://blog.example.com/?p=2</guid>
+      <title>Second Post</title>
+      <link>https://blog.example.com/second-post</link>
+      <pubDate>Fri, 15 Mar 2024 10:00:00 GMT</pubDate>
+      <category>rust</category>
+      <description>&lt;p&gt;Hello &lt;strong&gt;world&lt;/strong&gt;&lt;/p&gt;</description>
+    </item>
+    <item>
+      <guid>https://blog.example.com/?p=1</guid>
+      <title>First Post</title>
+      <link>https://blog.example.com/first-post</link>
+      <pubDate>Mon, 01 Jan 2024 10:00:00 GMT</pubDate>
+      <description>Old news</description>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Bob's Notes</title>
+  <id>urn:uuid:feed</id>
+  <updated>2024-03-16T09:00:00Z</updated>
+  <entry>
+    <id>urn:uuid:entry-1</id>
+    <title>Atom Entry</title>
+    <link rel="alternate" href="/notes/atom-entry"/>
+    <published>2024-03-15T10:00:00Z</published>
+    <updated>2024-03-16T09:00:00Z</updated>
+    <author><name>Bob</name></author>
+    <content type="html">&lt;h2&gt;Intro&lt;/h2&gt;&lt;p&gt;See &lt;a href="https://example.com"&gt;this&lt;/a&gt;&lt;/p&gt;</content>
+  </entry>
+</feed>"#;
+
+    async fn serve(
+        body: &str,
+    ) -> std::result::Result<(MockServer, Url), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let feed_url = Url::parse(&format!("{}/feed.xml", server.uri()))?;
+        Ok((server, feed_url))
+    }
+
+    #[tokio::test]
+    async fn test_list_and_fetch_rss() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let (_server, feed_url) = serve(RSS).await?;
+        let puller = RssPuller::new(feed_url.clone(), &HttpOptions::default(), None)?;
+
+        assert_eq!(puller.verify_credentials().await?, "Alice's Blog");
+
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].id, "https://blog.example.com/?p=2");
+        assert_eq!(articles[0].platform, Platform::Rss(feed_url));
+        assert_eq!(articles[0].title, "Second Post");
+
+        let article = puller
+            .fetch_article("https://blog.example.com/?p=2")
+            .await?;
+        assert_eq!(article.body_markdown, "Hello **world**");
+        assert_eq!(article.tags, vec!["rust"]);
+        assert_eq!(
+            article.url.as_ref().map(Url::as_str),
+            Some("https://blog.example.com/second-post")
+        );
+        assert_eq!(article.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert!(!article.is_draft);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_applies_since_and_limit(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let (_server, feed_url) = serve(RSS).await?;
+        let puller = RssPuller::new(feed_url, &HttpOptions::default(), None)?;
+
+        let options = PullOptions {
+            since: Some("2024-02-01".parse()?),
+            ..PullOptions::default()
+        };
+        let articles = puller.list_articles(&options).await?;
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title, "Second Post");
+
+        let options = PullOptions {
+            limit: Some(1),
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_atom_entry() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let (server, feed_url) = serve(ATOM).await?;
+        let puller = RssPuller::new(feed_url, &HttpOptions::default(), None)?;
+
+        let article = puller.fetch_article("urn:uuid:entry-1").await?;
+        assert_eq!(article.title, "Atom Entry");
+        assert_eq!(article.author.as_deref(), Some("Bob"));
+        assert_eq!(article.edited_at, Some("2024-03-16T09:00:00Z".parse()?));
+        assert!(article.body_markdown.contains("Intro"));
+        assert!(article
+            .body_markdown
+            .contains("[this](https://example.com)"));
+
+        // Relative links resolve against the feed URL
+        let url = Url::parse(&format!("{}/notes/atom-entry", server.uri()))?;
+        assert_eq!(article.url.as_ref(), Some(&url));
+        assert_eq!(puller.fetch_article_by_url(&url).await?.title, "Atom Entry");
+
+        assert!(matches!(
+            puller.fetch_article("urn:uuid:missing").await,
+            Err(PullError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_feed_is_an_error() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let (_server, feed_url) = serve("<html>not a feed</html>").await?;
+        let puller = RssPuller::new(feed_url, &HttpOptions::default(), None)?;
+
+        assert!(matches!(
+            puller.list_articles(&PullOptions::default()).await,
+            Err(PullError::Feed(_))
+        ));
+        Ok(())
+    }
+}
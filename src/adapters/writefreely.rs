@@ -0,0 +1,445 @@
+//! Puller for WriteFreely blogs (write.as or self-hosted), using the JSON API.
+//!
+//! A blog is a collection identified by its alias. Collection posts are already
+//! markdown, so bodies are kept as written. Requests are authenticated with an
+//! access token from `POST /api/auth/login`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{
+    check_response, paginate, send_with_retry, ArticleMetadata, HttpOptions, ListPage, PullOptions,
+    Puller, RateLimiter, RetryConfig,
+};
+use crate::article::{ArticleStats, PulledArticle};
+use crate::error::{PullError, RequestContext, Result};
+use crate::platform::Platform;
+use crate::writefreely::WriteFreelyInstance;
+
+/// Posts per page of a collection listing, fixed by WriteFreely.
+const PER_PAGE: usize = 10;
+
+/// Every response wraps its payload in `{"code": ..., "data": ...}`.
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct WfCollection {
+    #[serde(default)]
+    posts: Vec<WfPost>,
+    #[serde(default)]
+    total_posts: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WfPost {
+    id: String,
+    #[serde(default)]
+    slug: Option<String>,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: String,
+    created: Option<DateTime<Utc>>,
+    updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WfUser {
+    username: String,
+}
+
+impl WfPost {
+    /// Posts without a title show the start of their body instead; the slug is
+    /// the closest thing to a name for them.
+    fn display_title(&self) -> String {
+        if !self.title.is_empty() {
+            return self.title.clone();
+        }
+        self.slug.clone().unwrap_or_else(|| self.id.clone())
+    }
+
+    fn into_pulled_article(self, platform: Platform, url: Option<Url>) -> PulledArticle {
+        PulledArticle {
+            title: self.display_title(),
+            platform_id: self.id,
+            platform,
+            body_markdown: self.body.trim().to_string(),
+            published_at: self.created,
+            edited_at: self.updated,
+            url,
+            tags: self.tags,
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        }
+    }
+}
+
+pub struct WriteFreelyPuller {
+    instance: WriteFreelyInstance,
+    collection: String,
+    base_url: String,
+    client: reqwest::Client,
+    access_token: String,
+    retry: RetryConfig,
+    limiter: RateLimiter,
+}
+
+impl WriteFreelyPuller {
+    pub fn new(
+        instance: WriteFreelyInstance,
+        collection: String,
+        access_token: String,
+        http: &HttpOptions,
+        retry: Option<RetryConfig>,
+    ) -> Result<Self> {
+        Ok(Self {
+            base_url: instance.base_url(),
+            instance,
+            collection,
+            client: http.client(HeaderMap::new())?,
+            access_token,
+            retry: retry.unwrap_or_default(),
+            limiter: http.rate_limiter()?,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Public URL of a collection post: `https://{instance}/{collection}/{slug}`.
+    fn post_url(&self, slug: Option<&str>) -> Option<Url> {
+        let slug = slug?;
+        Url::parse(&format!(
+            "{}/{}/{slug}",
+            self.instance.site_url(),
+            self.collection
+        ))
+        .ok()
+    }
+
+    /// Requests an API endpoint, returning the unwrapped `data`, or `None` on 404.
+    ///
+    /// `operation` describes the request in errors, e.g. "list page 3".
+    async fn get<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        let mut url = Url::parse(&format!("{}/{endpoint}", self.base_url)).map_err(|e| {
+            PullError::InvalidConfig(format!("Invalid WriteFreely instance URL: {e}"))
+        })?;
+        url.query_pairs_mut().extend_pairs(query);
+
+        async {
+            let response = send_with_retry(url.as_str(), &self.retry, &self.limiter, || {
+                self.client
+                    .get(url.clone())
+                    .header("authorization", format!("Token {}", self.access_token))
+            })
+            .await?;
+
+            match response.status().as_u16() {
+                401 | 403 => return Err(PullError::Unauthorized(self.platform().to_string())),
+                404 | 410 => return Ok(None),
+                _ => {}
+            }
+            let response = check_response(response).await?;
+
+            let envelope: Envelope<T> = response.json().await?;
+            Ok(Some(envelope.data))
+        }
+        .await
+        .context(operation, url.as_str())
+    }
+
+    /// Lists one page of the collection's posts, newest first, keeping those
+    /// that pass `options`.
+    ///
+    /// `collected` is the number of posts listed on earlier pages.
+    async fn list_page(
+        &self,
+        options: &PullOptions,
+        page: u32,
+        collected: usize,
+    ) -> Result<ListPage> {
+        if options.limit_reached(collected) {
+            return Ok(ListPage::default());
+        }
+
+        let listing: WfCollection = self
+            .get(
+                &format!("list page {page}"),
+                &format!("collections/{}/posts", self.collection),
+                &[("page", &page.to_string())],
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(self.collection.clone()))?;
+
+        // Pages are full until the last one; the total (when sent) spares the
+        // request for an empty page after it
+        let seen = usize::try_from(page)
+            .unwrap_or(usize::MAX)
+            .saturating_mul(PER_PAGE);
+        let has_more = listing.posts.len() >= PER_PAGE
+            && !listing.total_posts.is_some_and(|total| seen >= total);
+
+        let (posts, done) =
+            options.take_newest_first(page, collected, listing.posts, |post| post.created);
+        let articles = posts
+            .into_iter()
+            .map(|post| ArticleMetadata {
+                title: post.display_title(),
+                platform: self.platform(),
+                published_at: post.created,
+                edited_at: post.updated,
+                url: self.post_url(post.slug.as_deref()),
+                slug: post.slug,
+                id: post.id,
+                is_draft: false,
+            })
+            .collect();
+        Ok(ListPage {
+            articles,
+            next_page: (has_more && !done).then_some(page + 1),
+        })
+    }
+}
+
+#[async_trait]
+impl Puller for WriteFreelyPuller {
+    fn platform(&self) -> Platform {
+        Platform::WriteFreely {
+            instance: self.instance.clone(),
+            collection: self.collection.clone(),
+        }
+    }
+
+    async fn verify_credentials(&self) -> Result<String> {
+        let user: WfUser = self
+            .get("read user", "me", &[])
+            .await?
+            .ok_or_else(|| PullError::NotFound(self.base_url.clone()))?;
+        Ok(user.username)
+    }
+
+    fn list_articles_stream<'a>(
+        &'a self,
+        options: &'a PullOptions,
+    ) -> BoxStream<'a, Result<ArticleMetadata>> {
+        // Only published posts belong to a collection; drafts stay unassigned
+        if options.drafts_only {
+            return stream::empty().boxed();
+        }
+        paginate(move |page, collected| self.list_page(options, page, collected))
+    }
+
+    async fn fetch_article(&self, id: &str) -> Result<PulledArticle> {
+        let post: WfPost = self
+            .get(&format!("fetch post {id}"), &format!("posts/{id}"), &[])
+            .await?
+            .ok_or_else(|| PullError::NotFound(id.to_string()))?;
+        let url = self.post_url(post.slug.as_deref());
+        Ok(post.into_pulled_article(self.platform(), url))
+    }
+
+    async fn fetch_article_by_url(&self, url: &Url) -> Result<PulledArticle> {
+        // Collection post URLs end in the slug: https://{instance}/{collection}/{slug}
+        let slug = url
+            .path_segments()
+            .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+            .ok_or_else(|| PullError::NotFound(url.to_string()))?;
+
+        let post: WfPost = self
+            .get(
+                &format!("fetch post {slug}"),
+                &format!("collections/{}/posts/{slug}", self.collection),
+                &[],
+            )
+            .await?
+            .ok_or_else(|| PullError::NotFound(url.to_string()))?;
+        let post_url = self.post_url(post.slug.as_deref());
+        Ok(post.into_pulled_article(self.platform(), post_url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn post(id: &str, created: &str) -> Value {
+        json!({
+            "id": id,
+            "slug": format!("post-{id}"),
+            "title": format!("Post {id}"),
+            "body": "Some *markdown*",
+            "created": created,
+            "updated": created,
+            "tags": [],
+        })
+    }
+
+    fn puller(
+        server: &MockServer,
+    ) -> std::result::Result<WriteFreelyPuller, Box<dyn std::error::Error>> {
+        Ok(WriteFreelyPuller::new(
+            WriteFreelyInstance::Custom {
+                domain: "blog.example.com".to_string(),
+            },
+            "alice".to_string(),
+            "access-token".to_string(),
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_base_url(&format!("{}/api", server.uri())))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_maps_fields() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/posts/rf3t35fkax0aw"))
+            .and(header("authorization", "Token access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": {
+                    "id": "rf3t35fkax0aw",
+                    "slug": "hello-writefreely",
+                    "appearance": "norm",
+                    "language": "en",
+                    "title": "Hello WriteFreely",
+                    "body": "## Intro\n\nPlain *markdown* #rust\n",
+                    "created": "2024-03-15T10:00:00Z",
+                    "updated": "2024-03-16T09:00:00Z",
+                    "tags": ["rust"],
+                    "views": 42,
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let article = puller(&server)?.fetch_article("rf3t35fkax0aw").await?;
+        assert_eq!(article.title, "Hello WriteFreely");
+        assert_eq!(article.body_markdown, "## Intro\n\nPlain *markdown* #rust");
+        assert_eq!(article.tags, vec!["rust"]);
+        assert_eq!(article.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert_eq!(article.edited_at, Some("2024-03-16T09:00:00Z".parse()?));
+        assert_eq!(
+            article.url.as_ref().map(Url::as_str),
+            Some("https://blog.example.com/alice/hello-writefreely")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_article_by_url_uses_slug(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/collections/alice/posts/post-7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": post("7", "2024-03-15T10:00:00Z"),
+            })))
+            .mount(&server)
+            .await;
+
+        let article = puller(&server)?
+            .fetch_article_by_url(&Url::parse("https://blog.example.com/alice/post-7")?)
+            .await?;
+        assert_eq!(article.platform_id, "7");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_follows_pagination() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        let first_page: Vec<Value> = (0..PER_PAGE)
+            .map(|i| {
+                post(
+                    &format!("{}", 20 - i),
+                    &format!("2024-02-{:02}T10:00:00Z", 20 - i),
+                )
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/api/collections/alice/posts"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": {"alias": "alice", "total_posts": 11, "posts": first_page},
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/collections/alice/posts"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 200,
+                "data": {
+                    "alias": "alice",
+                    "total_posts": 11,
+                    "posts": [post("1", "2024-01-01T10:00:00Z")],
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let puller = puller(&server)?;
+        let articles = puller.list_articles(&PullOptions::default()).await?;
+        assert_eq!(articles.len(), 11);
+        assert_eq!(articles[0].id, "20");
+        assert_eq!(articles[10].id, "1");
+        assert_eq!(articles[10].slug.as_deref(), Some("post-1"));
+
+        let options = PullOptions {
+            since: Some("2024-02-15".parse()?),
+            ..PullOptions::default()
+        };
+        assert_eq!(puller.list_articles(&options).await?.len(), 6);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_unauthorized(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/me"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+                "code": 401,
+                "error_msg": "Invalid access token.",
+            })))
+            .mount(&server)
+            .await;
+
+        assert!(matches!(
+            puller(&server)?.verify_credentials().await,
+            Err(PullError::Unauthorized(_))
+        ));
+        Ok(())
+    }
+}
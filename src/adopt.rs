@@ -0,0 +1,267 @@
+//! Rebuilds pull state from articles already on disk, for `puller adopt`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::article::{read_platform_id, DEFAULT_EXTENSION};
+use crate::comments::COMMENTS_SUFFIX;
+use crate::error::Result;
+use crate::state::PullState;
+use crate::writer::SERIES_INDEX_FILENAME;
+
+#[derive(Debug, Default)]
+pub struct AdoptReport {
+    /// Number of files recorded in the state
+    pub adopted: usize,
+    /// Markdown files without a platform id comment, relative to the output directory
+    pub unrecognized: Vec<String>,
+}
+
+/// Records every markdown file under `output_dir` that carries a `# Platform ID`
+/// comment in `state`, using the file's modification time as `pulled_at`.
+///
+/// Files written with `--no-tracking-marker` have no comment; the ones `state`
+/// already tracks by path count as adopted and keep their entry.
+pub fn adopt_directory(output_dir: &Path, state: &mut PullState) -> Result<AdoptReport> {
+    let mut files = Vec::new();
+    collect_markdown_files(output_dir, DEFAULT_EXTENSION, &mut files)?;
+    files.sort();
+
+    let mut report = AdoptReport::default();
+    for path in files {
+        let relative_path = relative_path(output_dir, &path);
+        let content = std::fs::read_to_string(&path)?;
+
+        let Some(platform_id) = read_platform_id(&content) else {
+            if state.find_by_path(&relative_path).is_some() {
+                report.adopted += 1;
+            } else {
+                report.unrecognized.push(relative_path);
+            }
+            continue;
+        };
+
+        let pulled_at: DateTime<Utc> = std::fs::metadata(&path)?.modified()?.into();
+        state.mark_pulled_at(platform_id, relative_path, pulled_at);
+        report.adopted += 1;
+    }
+
+    Ok(report)
+}
+
+/// Pulled files under an output directory by platform id, for picking up files a
+/// pull wrote but stopped before saving the state.
+///
+/// The directory is only scanned on the first lookup, so a run that finds
+/// everything in the state never reads the files.
+pub struct PulledFiles<'a> {
+    output_dir: &'a Path,
+    extension: &'a str,
+    index: Option<HashMap<String, PathBuf>>,
+}
+
+impl<'a> PulledFiles<'a> {
+    /// Looks for files with the writer's `extension` (without the dot).
+    pub const fn new(output_dir: &'a Path, extension: &'a str) -> Self {
+        Self {
+            output_dir,
+            extension,
+            index: None,
+        }
+    }
+
+    fn index(&mut self) -> Result<&HashMap<String, PathBuf>> {
+        if self.index.is_none() {
+            let mut files = Vec::new();
+            collect_markdown_files(self.output_dir, self.extension, &mut files)?;
+
+            let mut index = HashMap::new();
+            for path in files {
+                if let Some(platform_id) = read_platform_id(&std::fs::read_to_string(&path)?) {
+                    index.insert(platform_id, path);
+                }
+            }
+            self.index = Some(index);
+        }
+        Ok(self.index.get_or_insert_with(HashMap::new))
+    }
+
+    /// Records the file found for `platform_id` in `state`, returning its path.
+    ///
+    /// A file older than the article's last edit is left out, so the article is
+    /// pulled again.
+    pub fn reconcile(
+        &mut self,
+        platform_id: &str,
+        edited_at: Option<DateTime<Utc>>,
+        state: &mut PullState,
+    ) -> Result<Option<String>> {
+        let output_dir = self.output_dir;
+        let Some(path) = self.index()?.get(platform_id) else {
+            return Ok(None);
+        };
+        let modified: DateTime<Utc> = std::fs::metadata(path)?.modified()?.into();
+        if edited_at.is_some_and(|edited_at| edited_at > modified) {
+            return Ok(None);
+        }
+
+        let relative_path = relative_path(output_dir, path);
+        state.mark_pulled_at(platform_id.to_string(), relative_path.clone(), modified);
+        Ok(Some(relative_path))
+    }
+}
+
+/// Collects article files with `extension` (without the dot) under `dir`, leaving
+/// out series indexes and comment files.
+pub fn collect_markdown_files(dir: &Path, extension: &str, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_markdown_files(&path, extension, files)?;
+        } else if path.extension().is_some_and(|ext| ext == extension)
+            && path.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy();
+                name != SERIES_INDEX_FILENAME && !name.ends_with(COMMENTS_SUFFIX)
+            })
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Formats `path` relative to `output_dir` with `/` separators, as the writer does.
+pub fn relative_path(output_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(output_dir)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_adopt_directory() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::create_dir_all(dir.path().join("devto"))?;
+        std::fs::write(
+            dir.path().join("devto/2024-03-15-hello.md"),
+            "---\ntitle: Hello\n# Platform ID: devto:123\n---\n\nBody\n",
+        )?;
+        std::fs::write(
+            dir.path().join("2024-03-16-vibe.md"),
+            "---\ntitle: Vibe\n# Platform ID: vibe:7\n---\n\nBody\n",
+        )?;
+        std::fs::write(dir.path().join("notes.md"), "# Just notes\n")?;
+        std::fs::write(
+            dir.path().join("devto/_index.md"),
+            "---\ntitle: Series\n---\n",
+        )?;
+        std::fs::write(
+            dir.path().join("devto/2024-03-15-hello.comments.md"),
+            "# Comments on \"Hello\"\n",
+        )?;
+        std::fs::write(dir.path().join("image.png"), "not markdown")?;
+
+        let mut state = PullState::default();
+        let report = adopt_directory(dir.path(), &mut state)?;
+
+        assert_eq!(report.adopted, 2);
+        assert_eq!(report.unrecognized, vec!["notes.md".to_string()]);
+        assert_eq!(
+            state.get_local_path("devto:123"),
+            Some("devto/2024-03-15-hello.md")
+        );
+        assert_eq!(state.get_local_path("vibe:7"), Some("2024-03-16-vibe.md"));
+
+        let modified: DateTime<Utc> = std::fs::metadata(dir.path().join("2024-03-16-vibe.md"))?
+            .modified()?
+            .into();
+        assert_eq!(state.pulled["vibe:7"].pulled_at, modified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::create_dir_all(dir.path().join("devto"))?;
+        std::fs::write(
+            dir.path().join("devto/2024-03-15-hello.md"),
+            "---\ntitle: Hello\n# Platform ID: devto:123\n---\n\nBody\n",
+        )?;
+        std::fs::write(dir.path().join("notes.md"), "# Just notes\n")?;
+
+        let mut files = PulledFiles::new(dir.path(), DEFAULT_EXTENSION);
+
+        // Edited after the file was written, so it has to be pulled again
+        let mut state = PullState::default();
+        let edited_at = Utc::now() + chrono::Duration::hours(1);
+        let recorded = files.reconcile("devto:123", Some(edited_at), &mut state)?;
+        assert_eq!(recorded, None);
+        assert!(!state.is_pulled("devto:123"));
+
+        let recorded = files.reconcile("devto:123", None, &mut state)?;
+        assert_eq!(recorded.as_deref(), Some("devto/2024-03-15-hello.md"));
+        assert_eq!(
+            state.get_local_path("devto:123"),
+            Some("devto/2024-03-15-hello.md")
+        );
+
+        assert_eq!(files.reconcile("devto:456", None, &mut state)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_crlf_and_custom_extension(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::write(
+            dir.path().join("2024-03-15-hello.mdx"),
+            "---\r\ntitle: Hello\r\n# Platform ID: devto:123\r\n---\r\n\r\nBody\r\n",
+        )?;
+        std::fs::write(
+            dir.path().join("2024-03-16-other.md"),
+            "---\ntitle: Other\n# Platform ID: devto:456\n---\n",
+        )?;
+
+        let mut files = PulledFiles::new(dir.path(), "mdx");
+        let mut state = PullState::default();
+        let recorded = files.reconcile("devto:123", None, &mut state)?;
+        assert_eq!(recorded.as_deref(), Some("2024-03-15-hello.mdx"));
+
+        // Only files with the configured extension are articles of this run
+        assert_eq!(files.reconcile("devto:456", None, &mut state)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_adopt_without_tracking_marker() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::write(
+            dir.path().join("2024-03-15-hello.md"),
+            "---\ntitle: Hello\n---\n",
+        )?;
+        std::fs::write(dir.path().join("notes.md"), "# Just notes\n")?;
+
+        let mut state = PullState::default();
+        let pulled_at = "2024-03-20T10:00:00Z".parse()?;
+        state.mark_pulled_at(
+            "devto:123".to_string(),
+            "2024-03-15-hello.md".to_string(),
+            pulled_at,
+        );
+
+        let report = adopt_directory(dir.path(), &mut state)?;
+        assert_eq!(report.adopted, 1);
+        assert_eq!(report.unrecognized, vec!["notes.md".to_string()]);
+        assert_eq!(state.pulled["devto:123"].pulled_at, pulled_at);
+        Ok(())
+    }
+}
@@ -1,12 +1,26 @@
 use std::fmt::Write;
 
 use chrono::{DateTime, Utc};
+use deunicode::deunicode;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::error::Result;
+use crate::error::{PullError, Result};
+use crate::frontmatter::{FrontmatterFormat, FrontmatterOptions, FrontmatterWriter};
 use crate::platform::Platform;
 
+/// Default maximum length of the slug part of generated filenames.
+pub const DEFAULT_MAX_SLUG_LEN: usize = 80;
+
+/// Default extension of generated article files, without the dot.
+pub const DEFAULT_EXTENSION: &str = "md";
+
+/// Slug used when a title has no characters that survive slugification.
+const UNTITLED_SLUG: &str = "untitled";
+
+/// Prefix of the frontmatter comment that records an article's platform id.
+const PLATFORM_ID_PREFIX: &str = "# Platform ID: ";
+
 #[derive(Debug, Clone)]
 pub struct PulledArticle {
     pub platform_id: String,
@@ -14,59 +28,76 @@ pub struct PulledArticle {
     pub title: String,
     pub body_markdown: String,
     pub published_at: Option<DateTime<Utc>>,
-    #[allow(dead_code)] // Reserved for future use (e.g., verbose output)
+    pub edited_at: Option<DateTime<Utc>>,
     pub url: Option<Url>,
     pub tags: Vec<String>,
     pub series: Option<String>,
     pub canonical_url: Option<Url>,
+    pub cover_image: Option<Url>,
+    /// Username of the writer
+    pub author: Option<String>,
+    /// Organization the article was published under, if any
+    pub organization: Option<Organization>,
+    /// Engagement numbers, only written with `--include-stats`
+    pub stats: ArticleStats,
+    /// HTTP validator used for conditional re-fetches; not written to the file
+    pub etag: Option<String>,
     pub is_draft: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Frontmatter {
-    title: String,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Organization {
+    pub name: String,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleStats {
     #[serde(skip_serializing_if = "Option::is_none")]
-    scheduled_at: Option<DateTime<Utc>>,
-    status: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    tags: Vec<String>,
+    pub reading_time_minutes: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    series: Option<String>,
+    pub reactions_count: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    canonical_url: Option<Url>,
+    pub comments_count: Option<u32>,
 }
 
 impl PulledArticle {
-    fn to_frontmatter(&self) -> Frontmatter {
-        Frontmatter {
-            title: self.title.clone(),
-            scheduled_at: self.published_at,
-            status: if self.is_draft {
-                "draft".to_string()
-            } else {
-                "publish".to_string()
-            },
-            tags: self.tags.clone(),
-            series: self.series.clone(),
-            canonical_url: self.canonical_url.clone(),
-        }
+    pub fn to_markdown(&self) -> Result<String> {
+        self.to_markdown_as(FrontmatterFormat::Standard, &FrontmatterOptions::default())
     }
 
-    pub fn to_markdown(&self) -> Result<String> {
-        let frontmatter = self.to_frontmatter();
-        let yaml = serde_yaml::to_string(&frontmatter)?;
+    /// Renders the article with `format` frontmatter. Without `options.tracking_marker`
+    /// the `# Platform ID` comment is left out, so only the state ties the file
+    /// to the article.
+    pub fn to_markdown_as(
+        &self,
+        format: FrontmatterFormat,
+        options: &FrontmatterOptions,
+    ) -> Result<String> {
+        self.to_markdown_with(format.writer(), options)
+    }
+
+    /// Renders the article with the frontmatter `writer` produces, see `to_markdown_as`.
+    pub fn to_markdown_with(
+        &self,
+        writer: &dyn FrontmatterWriter,
+        options: &FrontmatterOptions,
+    ) -> Result<String> {
+        let yaml = writer.render(self, options)?;
 
         let mut output = String::new();
         output.push_str("---\n");
         output.push_str(&yaml);
 
         // Add platform ID comment for tracking
-        writeln!(
-            output,
-            "# Platform ID: {}:{}",
-            self.platform, self.platform_id
-        )
-        .expect("String write failed");
+        if options.tracking_marker {
+            writeln!(
+                output,
+                "{PLATFORM_ID_PREFIX}{}:{}",
+                self.platform, self.platform_id
+            )
+            .expect("String write failed");
+        }
 
         output.push_str("---\n\n");
         output.push_str(&self.body_markdown);
@@ -79,27 +110,158 @@ impl PulledArticle {
         Ok(output)
     }
 
-    pub fn generate_filename(&self) -> String {
+    /// Identifies the original of a cross-posted article: its normalized canonical
+    /// URL, or its own URL when it doesn't name another source.
+    pub fn canonical_key(&self) -> Option<String> {
+        self.canonical_url
+            .as_ref()
+            .or(self.url.as_ref())
+            .map(normalize_url)
+    }
+
+    /// The slug the platform published the article under, see `url_slug`.
+    pub fn url_slug(&self) -> Option<String> {
+        self.url.as_ref().and_then(url_slug)
+    }
+
+    pub fn generate_filename(&self, max_slug_len: usize, extension: &str) -> String {
         let date_prefix = self.published_at.map_or_else(
             || "draft".to_string(),
             |dt| dt.format("%Y-%m-%d").to_string(),
         );
 
-        let slug = slugify(&self.title);
-        format!("{date_prefix}-{slug}.md")
+        let slug = slugify(&self.title, max_slug_len);
+        format!("{date_prefix}-{slug}.{extension}")
+    }
+}
+
+/// The slug an article was published under: the last segment of its URL, e.g.
+/// `my-post-1a2b` for `https://dev.to/alice/my-post-1a2b`.
+pub fn url_slug(url: &Url) -> Option<String> {
+    let segment = url
+        .path_segments()?
+        .rev()
+        .find(|segment| !segment.is_empty())?;
+    let slug = segment
+        .strip_suffix(".html")
+        .or_else(|| segment.strip_suffix(".htm"))
+        .unwrap_or(segment);
+    (!slug.is_empty()).then(|| slug.to_string())
+}
+
+/// Normalizes a URL for comparison: `https`, no `www.`, no fragment, no tracking
+/// (`utm_*`) parameters and no trailing slash.
+pub fn normalize_url(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let path = url.path().trim_end_matches('/');
+
+    let mut normalized = format!("https://{host}");
+    if let Some(port) = url.port() {
+        write!(normalized, ":{port}").expect("String write failed");
+    }
+    normalized.push_str(path);
+
+    let query: Vec<String> = url
+        .query_pairs()
+        .filter(|(name, _)| !name.starts_with("utm_"))
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    if !query.is_empty() {
+        normalized.push('?');
+        normalized.push_str(&query.join("&"));
+    }
+    normalized
+}
+
+/// Checks a file extension given on the command line, with or without its leading
+/// dot, and returns it without the dot.
+pub fn parse_extension(extension: &str) -> Result<String> {
+    let trimmed = extension.strip_prefix('.').unwrap_or(extension);
+    if trimmed.is_empty() || trimmed.contains(['/', '\\']) {
+        return Err(PullError::InvalidConfig(format!(
+            "Invalid file extension {extension:?}: must be non-empty and contain no path separators"
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Lowercases and trims tags, dropping empty ones and later duplicates so the
+/// first occurrence keeps its place.
+pub fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
     }
+    normalized
+}
+
+/// Reads the `title` key from a markdown file's YAML frontmatter.
+pub fn read_frontmatter_title(content: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct TitleOnly {
+        title: String,
+    }
+
+    serde_yaml::from_str::<TitleOnly>(frontmatter_block(content)?)
+        .ok()
+        .map(|fm| fm.title)
 }
 
-fn slugify(title: &str) -> String {
-    title
-        .to_lowercase()
-        .chars()
-        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
+/// Reads the `platform:id` from the `# Platform ID` comment in an article's frontmatter.
+pub fn read_platform_id(content: &str) -> Option<String> {
+    frontmatter_block(content)?
+        .lines()
+        .find_map(|line| line.strip_prefix(PLATFORM_ID_PREFIX))
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Returns the YAML between the `---` fences of a file written with either line
+/// ending.
+fn frontmatter_block(content: &str) -> Option<&str> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+    let end = rest.find("\n---")?;
+    Some(&rest[..end])
+}
+
+/// Builds a URL-safe slug of at most `max_len` characters.
+///
+/// Long slugs are cut on a word boundary; a title that produces no characters at
+/// all (e.g. pure punctuation) falls back to `untitled`.
+pub fn slugify(title: &str, max_len: usize) -> String {
+    // Transliterate to ASCII first so "Über" becomes "uber" rather than "ber"
+    let lowered = deunicode(title).to_lowercase();
+    let words = lowered
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty());
+
+    let mut slug = String::new();
+    for word in words {
+        let separator = usize::from(!slug.is_empty());
+        if slug.len() + separator + word.len() > max_len {
+            if slug.is_empty() {
+                // A single word longer than the limit is cut mid-word
+                slug.push_str(&word[..max_len.min(word.len())]);
+            }
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+
+    if slug.is_empty() {
+        UNTITLED_SLUG.to_string()
+    } else {
+        slug
+    }
 }
 
 #[cfg(test)]
@@ -107,15 +269,79 @@ mod tests {
     use super::*;
     use crate::forem::ForemInstance;
 
+    fn without_marker() -> FrontmatterOptions {
+        FrontmatterOptions {
+            tracking_marker: false,
+            ..FrontmatterOptions::default()
+        }
+    }
+
     #[test]
     fn test_slugify() {
-        assert_eq!(slugify("Hello World"), "hello-world");
+        assert_eq!(slugify("Hello World", DEFAULT_MAX_SLUG_LEN), "hello-world");
         assert_eq!(
-            slugify("Building CLI Tools in Rust"),
+            slugify("Building CLI Tools in Rust", DEFAULT_MAX_SLUG_LEN),
             "building-cli-tools-in-rust"
         );
-        assert_eq!(slugify("What's New?"), "what-s-new");
-        assert_eq!(slugify("Multiple   Spaces"), "multiple-spaces");
+        assert_eq!(slugify("What's New?", DEFAULT_MAX_SLUG_LEN), "what-s-new");
+        assert_eq!(
+            slugify("Multiple   Spaces", DEFAULT_MAX_SLUG_LEN),
+            "multiple-spaces"
+        );
+    }
+
+    #[test]
+    fn test_slugify_transliterates() {
+        assert_eq!(
+            slugify("Über Rust: Straße", DEFAULT_MAX_SLUG_LEN),
+            "uber-rust-strasse"
+        );
+        assert_eq!(
+            slugify("Café à la crème", DEFAULT_MAX_SLUG_LEN),
+            "cafe-a-la-creme"
+        );
+        assert_eq!(slugify("Привет мир", DEFAULT_MAX_SLUG_LEN), "privet-mir");
+    }
+
+    #[test]
+    fn test_slugify_empty_result_falls_back() {
+        assert_eq!(slugify("?!... ---", DEFAULT_MAX_SLUG_LEN), "untitled");
+        assert_eq!(slugify("", DEFAULT_MAX_SLUG_LEN), "untitled");
+    }
+
+    #[test]
+    fn test_slugify_truncates_on_word_boundary() {
+        let title = "word ".repeat(60);
+        assert_eq!(title.len(), 300);
+
+        let slug = slugify(&title, DEFAULT_MAX_SLUG_LEN);
+        assert!(slug.len() <= DEFAULT_MAX_SLUG_LEN);
+        assert!(slug.split('-').all(|w| w == "word"));
+        assert_eq!(slug.len(), 79);
+    }
+
+    #[test]
+    fn test_slugify_truncates_single_long_word() {
+        assert_eq!(slugify(&"a".repeat(300), 10), "aaaaaaaaaa");
+    }
+
+    #[test]
+    fn test_slugify_emoji_is_not_empty() {
+        assert!(!slugify("🦀🚀", DEFAULT_MAX_SLUG_LEN).is_empty());
+    }
+
+    #[test]
+    fn test_read_frontmatter_title() {
+        let content =
+            "---\ntitle: 'Hello: World'\nstatus: publish\n# Platform ID: devto:1\n---\n\nBody\n";
+        assert_eq!(
+            read_frontmatter_title(content),
+            Some("Hello: World".to_string())
+        );
+        assert_eq!(read_frontmatter_title("No frontmatter"), None);
+
+        let crlf = "---\r\ntitle: Hello\r\n---\r\n\r\nBody\r\n";
+        assert_eq!(read_frontmatter_title(crlf), Some("Hello".to_string()));
     }
 
     #[test]
@@ -126,17 +352,27 @@ mod tests {
             title: "Building CLI Tools in Rust".to_string(),
             body_markdown: "Content".to_string(),
             published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
             url: None,
             tags: vec![],
             series: None,
             canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
             is_draft: false,
         };
 
         assert_eq!(
-            article.generate_filename(),
+            article.generate_filename(DEFAULT_MAX_SLUG_LEN, DEFAULT_EXTENSION),
             "2024-03-15-building-cli-tools-in-rust.md"
         );
+        assert_eq!(
+            article.generate_filename(DEFAULT_MAX_SLUG_LEN, "markdown"),
+            "2024-03-15-building-cli-tools-in-rust.markdown"
+        );
         Ok(())
     }
 
@@ -148,13 +384,484 @@ mod tests {
             title: "My Draft".to_string(),
             body_markdown: "Content".to_string(),
             published_at: None,
+            edited_at: None,
             url: None,
             tags: vec![],
             series: None,
             canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
             is_draft: true,
         };
 
-        assert_eq!(article.generate_filename(), "draft-my-draft.md");
+        assert_eq!(
+            article.generate_filename(DEFAULT_MAX_SLUG_LEN, DEFAULT_EXTENSION),
+            "draft-my-draft.md"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_cover_image() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "With Cover".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: Some("https://example.com/cover.png".parse()?),
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        assert!(article
+            .to_markdown()?
+            .contains("cover_image: https://example.com/cover.png"));
+
+        article.cover_image = None;
+        assert!(!article.to_markdown()?.contains("cover_image"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_updated() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Edited".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: Some("2024-04-01T08:30:00Z".parse()?),
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        assert!(article
+            .to_markdown()?
+            .contains("updated: 2024-04-01T08:30:00Z"));
+
+        article.edited_at = None;
+        assert!(!article.to_markdown()?.contains("updated:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_hugo() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Hugo Post".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: Some("2024-04-01T08:30:00Z".parse()?),
+            url: None,
+            tags: vec!["rust".to_string()],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let markdown =
+            article.to_markdown_as(FrontmatterFormat::Hugo, &FrontmatterOptions::default())?;
+        assert!(markdown.contains("title: Hugo Post\n"));
+        assert!(markdown.contains("date: 2024-03-15T10:00:00Z\n"));
+        assert!(markdown.contains("lastmod: 2024-04-01T08:30:00Z\n"));
+        assert!(markdown.contains("draft: false\n"));
+        assert!(markdown.contains("tags:\n- rust\n"));
+        assert!(!markdown.contains("status:"));
+        assert!(!markdown.contains("updated:"));
+
+        // A scheduled draft keeps its date, which Hugo treats as not yet published
+        article.is_draft = true;
+        article.published_at = Some("2999-01-01T00:00:00Z".parse()?);
+        let markdown =
+            article.to_markdown_as(FrontmatterFormat::Hugo, &FrontmatterOptions::default())?;
+        assert!(markdown.contains("date: 2999-01-01T00:00:00Z\n"));
+        assert!(markdown.contains("draft: true\n"));
+        assert!(!markdown.contains("scheduled_at:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_forem() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Forem Post".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: Some("2024-04-01T08:30:00Z".parse()?),
+            url: Some("https://dev.to/user/forem-post-abc".parse()?),
+            tags: vec!["rust".to_string(), "cli".to_string()],
+            series: Some("Rust CLI Series".to_string()),
+            canonical_url: Some("https://example.com/forem-post".parse()?),
+            cover_image: Some("https://example.com/cover.png".parse()?),
+            author: Some("user".to_string()),
+            organization: None,
+            stats: ArticleStats {
+                reading_time_minutes: Some(3),
+                reactions_count: None,
+                comments_count: None,
+            },
+            etag: None,
+            is_draft: false,
+        };
+
+        // The keys and types of Forem's editor frontmatter, and nothing else
+        let markdown = article.to_markdown_as(FrontmatterFormat::Forem, &without_marker())?;
+        let yaml = markdown
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("---\n"))
+            .map(|(yaml, _)| yaml)
+            .ok_or("no frontmatter")?;
+        let frontmatter: serde_yaml::Mapping = serde_yaml::from_str(yaml)?;
+        let keys: Vec<&str> = frontmatter.keys().filter_map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "title",
+                "published",
+                "tags",
+                "series",
+                "canonical_url",
+                "cover_image"
+            ]
+        );
+        assert_eq!(frontmatter["title"].as_str(), Some("Forem Post"));
+        assert_eq!(frontmatter["published"].as_bool(), Some(true));
+        assert_eq!(frontmatter["tags"].as_str(), Some("rust, cli"));
+        assert_eq!(frontmatter["series"].as_str(), Some("Rust CLI Series"));
+        assert_eq!(
+            frontmatter["canonical_url"].as_str(),
+            Some("https://example.com/forem-post")
+        );
+        assert_eq!(
+            frontmatter["cover_image"].as_str(),
+            Some("https://example.com/cover.png")
+        );
+
+        // Optional keys are left out rather than written empty
+        article.is_draft = true;
+        article.tags.clear();
+        article.series = None;
+        article.canonical_url = None;
+        article.cover_image = None;
+        let markdown =
+            article.to_markdown_as(FrontmatterFormat::Forem, &FrontmatterOptions::default())?;
+        assert!(markdown.starts_with("---\ntitle: Forem Post\npublished: false\n# Platform ID:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_frontmatter_published_uses_date() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Published".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let markdown = article.to_markdown()?;
+        assert!(markdown.contains("date: 2024-03-15T10:00:00Z"));
+        assert!(markdown.contains("status: publish"));
+        assert!(!markdown.contains("scheduled_at"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_frontmatter_future_draft_uses_scheduled_at(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Scheduled".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: Some("2999-01-01T09:00:00Z".parse()?),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: true,
+        };
+
+        let markdown = article.to_markdown()?;
+        assert!(markdown.contains("scheduled_at: 2999-01-01T09:00:00Z"));
+        assert!(markdown.contains("status: draft"));
+        assert!(!markdown.contains("date:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_frontmatter_dateless_draft() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Draft".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: true,
+        };
+
+        let markdown = article.to_markdown()?;
+        assert!(markdown.contains("status: draft"));
+        assert!(!markdown.contains("date:"));
+        assert!(!markdown.contains("scheduled_at"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_platform_id() {
+        let content = "---\ntitle: Hello\n# Platform ID: forem:open:42\n---\n\nBody\n";
+        assert_eq!(read_platform_id(content), Some("forem:open:42".to_string()));
+
+        let in_body = "---\ntitle: Hello\n---\n\n# Platform ID: devto:1\n";
+        assert_eq!(read_platform_id(in_body), None);
+        assert_eq!(read_platform_id("No frontmatter"), None);
+
+        let crlf = "---\r\ntitle: Hello\r\n# Platform ID: devto:1\r\n---\r\n\r\nBody\r\n";
+        assert_eq!(read_platform_id(crlf), Some("devto:1".to_string()));
+    }
+
+    #[test]
+    fn test_to_markdown_author_and_organization(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Team Post".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: Some("alice".to_string()),
+            organization: Some(Organization {
+                name: "Acme Inc".to_string(),
+                username: "acme".to_string(),
+            }),
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let markdown = article.to_markdown()?;
+        assert!(markdown.contains("author: alice\n"));
+        assert!(markdown.contains("organization:\n  name: Acme Inc\n  username: acme\n"));
+
+        article.organization = None;
+        assert!(!article.to_markdown()?.contains("organization"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_stats() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Popular".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats {
+                reading_time_minutes: Some(4),
+                reactions_count: Some(120),
+                comments_count: None,
+            },
+            etag: None,
+            is_draft: false,
+        };
+
+        let markdown = article.to_markdown()?;
+        assert!(markdown.contains("reading_time_minutes: 4\n"));
+        assert!(markdown.contains("reactions_count: 120\n"));
+        assert!(!markdown.contains("comments_count"));
+
+        article.stats = ArticleStats::default();
+        assert!(!article.to_markdown()?.contains("reading_time_minutes"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_extension() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(parse_extension("markdown")?, "markdown");
+        assert_eq!(parse_extension(".mdx")?, "mdx");
+        assert!(parse_extension("").is_err());
+        assert!(parse_extension(".").is_err());
+        assert!(parse_extension("md/evil").is_err());
+        assert!(parse_extension("..\\md").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let normalized = "https://blog.example.com/posts/hello";
+        for url in [
+            "https://blog.example.com/posts/hello",
+            "http://www.blog.example.com/posts/hello/",
+            "https://BLOG.example.com/posts/hello#comments",
+            "https://blog.example.com/posts/hello?utm_source=devto&utm_medium=web",
+        ] {
+            assert_eq!(normalize_url(&Url::parse(url)?), normalized, "{url}");
+        }
+        assert_eq!(
+            normalize_url(&Url::parse("https://example.com/post?id=2&utm_source=x")?),
+            "https://example.com/post?id=2"
+        );
+        assert_ne!(
+            normalize_url(&Url::parse("https://blog.example.com/posts/other")?),
+            normalized
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_slug() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Building CLI Tools".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: Some("https://dev.to/alice/building-cli-tools-4k2j".parse()?),
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+        assert!(article
+            .to_markdown()?
+            .contains("slug: building-cli-tools-4k2j\n"));
+
+        // Ghost URLs end in a slash, and some blogs serve .html pages
+        article.url = Some("https://blog.example.com/building-cli-tools/".parse()?);
+        assert_eq!(article.url_slug().as_deref(), Some("building-cli-tools"));
+        article.url = Some("https://blog.example.com/2024/03/cli.html".parse()?);
+        assert_eq!(article.url_slug().as_deref(), Some("cli"));
+
+        // Nothing to take from the URL, so the slug matches the generated filename
+        article.url = Some("https://blog.example.com/?p=2".parse()?);
+        assert!(article
+            .to_markdown()?
+            .contains("slug: building-cli-tools\n"));
+        article.url = None;
+        assert!(article
+            .to_markdown()?
+            .contains("slug: building-cli-tools\n"));
+        let short = FrontmatterOptions {
+            max_slug_len: 10,
+            ..FrontmatterOptions::default()
+        };
+        assert!(article
+            .to_markdown_as(FrontmatterFormat::Standard, &short)?
+            .contains("slug: building\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_without_tracking_marker(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Clean Export".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let markdown = article.to_markdown_as(FrontmatterFormat::Standard, &without_marker())?;
+        assert!(!markdown.contains(PLATFORM_ID_PREFIX));
+        assert_eq!(read_platform_id(&markdown), None);
+        assert_eq!(
+            read_platform_id(&article.to_markdown()?).as_deref(),
+            Some("devto:123")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = ["", "Rust", " rust ", "WebDev", "  ", "cli", "webdev"]
+            .into_iter()
+            .map(String::from);
+        assert_eq!(normalize_tags(tags), vec!["rust", "webdev", "cli"]);
     }
 }
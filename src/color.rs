@@ -0,0 +1,37 @@
+//! Decides whether output may use ANSI colors.
+//!
+//! Colors come from clap's help and error messages and from the text log format;
+//! both ask `color_enabled`. The progress bar is drawn without colors.
+
+use std::ffi::OsStr;
+use std::io::IsTerminal;
+
+/// Returns whether colored output is allowed: `--no-color` wasn't passed, `NO_COLOR`
+/// is unset or empty (see <https://no-color.org>), and stderr is a terminal.
+pub fn color_enabled(no_color_flag: bool) -> bool {
+    should_color(
+        no_color_flag,
+        std::env::var_os("NO_COLOR").as_deref(),
+        std::io::stderr().is_terminal(),
+    )
+}
+
+fn should_color(no_color_flag: bool, no_color_env: Option<&OsStr>, is_terminal: bool) -> bool {
+    let env_disables = no_color_env.is_some_and(|value| !value.is_empty());
+    !no_color_flag && !env_disables && is_terminal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(should_color(false, None, true));
+        assert!(!should_color(true, None, true));
+        assert!(!should_color(false, None, false));
+        assert!(!should_color(false, Some(OsStr::new("1")), true));
+        // An empty NO_COLOR does not disable colors
+        assert!(should_color(false, Some(OsStr::new("")), true));
+    }
+}
@@ -0,0 +1,121 @@
+//! Single-document export for `puller pull --combine`.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::article::{slugify, PulledArticle, DEFAULT_MAX_SLUG_LEN};
+
+/// Renders `articles` as one markdown document, oldest first with undated
+/// drafts at the end, behind a table of contents linking to each section.
+///
+/// Sections are anchored with an explicit `<a id>`, since renderers disagree on
+/// how headings turn into anchors.
+pub fn render_combined(articles: &mut [PulledArticle]) -> String {
+    sort_by_date(articles);
+
+    let mut used = HashSet::new();
+    let anchors: Vec<String> = articles
+        .iter()
+        .map(|article| {
+            let base = slugify(&article.title, DEFAULT_MAX_SLUG_LEN);
+            let mut anchor = base.clone();
+            let mut n = 2;
+            while !used.insert(anchor.clone()) {
+                anchor = format!("{base}-{n}");
+                n += 1;
+            }
+            anchor
+        })
+        .collect();
+
+    let mut output = String::from("# Articles\n\n## Contents\n\n");
+    for (article, anchor) in articles.iter().zip(&anchors) {
+        writeln!(
+            output,
+            "- [{}](#{anchor}) ({})",
+            article.title,
+            date_label(article)
+        )
+        .expect("String write failed");
+    }
+
+    for (article, anchor) in articles.iter().zip(&anchors) {
+        write!(
+            output,
+            "\n---\n\n<a id=\"{anchor}\"></a>\n\n## {}\n\n*{}*\n\n{}",
+            article.title,
+            date_label(article),
+            article.body_markdown.trim_end()
+        )
+        .expect("String write failed");
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Orders articles oldest first, with undated drafts at the end, as a book reads.
+pub fn sort_by_date(articles: &mut [PulledArticle]) {
+    articles.sort_by_key(|article| (article.published_at.is_none(), article.published_at));
+}
+
+/// Publication date shown under each article's title.
+pub fn date_label(article: &PulledArticle) -> String {
+    match article.published_at {
+        Some(published_at) if article.is_draft => {
+            format!("Draft, scheduled for {}", published_at.format("%Y-%m-%d"))
+        }
+        Some(published_at) => published_at.format("%Y-%m-%d").to_string(),
+        None => "Draft".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::article::ArticleStats;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+
+    fn article(id: &str, title: &str, published_at: Option<&str>) -> PulledArticle {
+        PulledArticle {
+            platform_id: id.to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: title.to_string(),
+            body_markdown: format!("Body of {id}\n"),
+            published_at: published_at.and_then(|d| d.parse().ok()),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: published_at.is_none(),
+        }
+    }
+
+    #[test]
+    fn test_render_combined() {
+        let mut articles = vec![
+            article("3", "Work in Progress", None),
+            article("2", "Hello", Some("2024-03-15T10:00:00Z")),
+            article("1", "Hello", Some("2024-01-01T10:00:00Z")),
+        ];
+
+        let combined = render_combined(&mut articles);
+        assert_eq!(
+            combined,
+            "# Articles\n\n## Contents\n\n\
+             - [Hello](#hello) (2024-01-01)\n\
+             - [Hello](#hello-2) (2024-03-15)\n\
+             - [Work in Progress](#work-in-progress) (Draft)\n\
+             \n---\n\n<a id=\"hello\"></a>\n\n## Hello\n\n*2024-01-01*\n\nBody of 1\n\
+             \n---\n\n<a id=\"hello-2\"></a>\n\n## Hello\n\n*2024-03-15*\n\nBody of 2\n\
+             \n---\n\n<a id=\"work-in-progress\"></a>\n\n## Work in Progress\n\n*Draft*\n\nBody of 3\n"
+        );
+    }
+}
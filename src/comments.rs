@@ -0,0 +1,124 @@
+//! Article comments, archived as a threaded markdown file next to the article.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::article::PulledArticle;
+
+/// Replaces the `.md` extension of an article file to name its comments file.
+pub const COMMENTS_SUFFIX: &str = ".comments.md";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub id: String,
+    /// Username of the commenter; `None` once the comment or account is deleted
+    pub author: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Comment text as markdown
+    pub body: String,
+    /// Deleted comments keep their place in the thread so replies stay nested
+    pub deleted: bool,
+    pub replies: Vec<Comment>,
+}
+
+/// Renders a comment tree as nested markdown list items, one level per reply.
+pub fn comments_to_markdown(article: &PulledArticle, comments: &[Comment]) -> String {
+    let mut output = format!("# Comments on \"{}\"\n\n", article.title);
+    for comment in comments {
+        write_comment(&mut output, comment, 0);
+    }
+    output
+}
+
+fn write_comment(output: &mut String, comment: &Comment, depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    if comment.deleted {
+        writeln!(output, "{indent}- *[deleted]*").expect("String write failed");
+    } else {
+        let author = comment.author.as_deref().unwrap_or("[unknown]");
+        write!(output, "{indent}- **{author}**").expect("String write failed");
+        if let Some(created_at) = comment.created_at {
+            write!(output, " · {}", created_at.format("%Y-%m-%d %H:%M UTC"))
+                .expect("String write failed");
+        }
+        output.push_str("\n\n");
+
+        for line in comment.body.lines() {
+            if line.trim().is_empty() {
+                output.push('\n');
+            } else {
+                writeln!(output, "{indent}  {line}").expect("String write failed");
+            }
+        }
+    }
+    output.push('\n');
+
+    for reply in &comment.replies {
+        write_comment(output, reply, depth + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::article::ArticleStats;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+
+    fn comment(id: &str, author: &str, body: &str, replies: Vec<Comment>) -> Comment {
+        Comment {
+            id: id.to_string(),
+            author: Some(author.to_string()),
+            created_at: "2024-03-15T10:30:00Z".parse().ok(),
+            body: body.to_string(),
+            deleted: false,
+            replies,
+        }
+    }
+
+    #[test]
+    fn test_comments_to_markdown_nests_replies() {
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "My Post".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+        let deleted = Comment {
+            id: "c2".to_string(),
+            author: None,
+            created_at: None,
+            body: String::new(),
+            deleted: true,
+            replies: vec![comment("c3", "carol", "Still here", vec![])],
+        };
+        let comments = vec![comment(
+            "c1",
+            "alice",
+            "Great post!\n\nThanks",
+            vec![deleted],
+        )];
+
+        assert_eq!(
+            comments_to_markdown(&article, &comments),
+            "# Comments on \"My Post\"\n\n\
+             - **alice** · 2024-03-15 10:30 UTC\n\n  Great post!\n\n  Thanks\n\n\
+             \x20 - *[deleted]*\n\n\
+             \x20   - **carol** · 2024-03-15 10:30 UTC\n\n      Still here\n\n"
+        );
+    }
+}
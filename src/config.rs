@@ -1,20 +1,146 @@
 use crate::error::{PullError, Result};
 use std::env;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
+#[derive(Clone)]
 pub struct Config {
+    /// Key read from a file, stdin or the credential store, used instead of the
+    /// platform's variable
+    pub api_key: Option<String>,
     pub forem_api_key: Option<String>,
+    pub ghost_content_api_key: Option<String>,
+    pub bluesky_app_password: Option<String>,
+    pub writefreely_access_token: Option<String>,
+    pub user_agent: Option<String>,
+    /// Articles per Forem list page, for debugging pagination (`PULLER_PER_PAGE`)
+    pub per_page: Option<u32>,
+    /// Forem API version requested in the `Accept` header (`PULLER_FOREM_API_VERSION`)
+    pub forem_api_version: Option<String>,
+    /// Where `pull` writes when no output directory is passed (`PULLER_OUTPUT_DIR`)
+    pub output_dir: Option<PathBuf>,
+    /// Forem user whose public articles are listed instead of the key owner's (`--username`)
+    pub username: Option<String>,
 }
 
 impl Config {
     pub fn from_env() -> Self {
         Self {
+            api_key: None,
             forem_api_key: env::var("VIBE_FOREM_API_KEY").ok(),
+            ghost_content_api_key: env::var("GHOST_CONTENT_API_KEY").ok(),
+            bluesky_app_password: env::var("BLUESKY_APP_PASSWORD").ok(),
+            writefreely_access_token: env::var("WRITEFREELY_ACCESS_TOKEN").ok(),
+            user_agent: env::var("PULLER_USER_AGENT").ok(),
+            per_page: env::var("PULLER_PER_PAGE")
+                .ok()
+                .and_then(|value| value.parse().ok()),
+            forem_api_version: env::var("PULLER_FOREM_API_VERSION").ok(),
+            output_dir: env::var_os("PULLER_OUTPUT_DIR")
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            username: None,
         }
     }
 
     pub fn forem_api_key(&self) -> Result<&str> {
-        self.forem_api_key
+        self.api_key
             .as_deref()
+            .or(self.forem_api_key.as_deref())
             .ok_or_else(|| PullError::MissingConfig("VIBE_FOREM_API_KEY".to_string()))
     }
+
+    pub fn ghost_content_api_key(&self) -> Result<&str> {
+        self.api_key
+            .as_deref()
+            .or(self.ghost_content_api_key.as_deref())
+            .ok_or_else(|| PullError::MissingConfig("GHOST_CONTENT_API_KEY".to_string()))
+    }
+
+    pub fn bluesky_app_password(&self) -> Result<&str> {
+        self.api_key
+            .as_deref()
+            .or(self.bluesky_app_password.as_deref())
+            .ok_or_else(|| PullError::MissingConfig("BLUESKY_APP_PASSWORD".to_string()))
+    }
+
+    pub fn writefreely_access_token(&self) -> Result<&str> {
+        self.api_key
+            .as_deref()
+            .or(self.writefreely_access_token.as_deref())
+            .ok_or_else(|| PullError::MissingConfig("WRITEFREELY_ACCESS_TOKEN".to_string()))
+    }
+}
+
+/// Reads an API key from a file, such as a mounted container secret.
+pub fn read_api_key_file(path: &Path) -> Result<String> {
+    let file = std::fs::File::open(path).map_err(|e| {
+        PullError::InvalidConfig(format!("Cannot read API key file {}: {e}", path.display()))
+    })?;
+    read_api_key(file, &path.display().to_string())
+}
+
+/// Reads an API key from `reader`, trimming surrounding whitespace.
+///
+/// `source` names where the key came from in error messages.
+pub fn read_api_key(mut reader: impl Read, source: &str) -> Result<String> {
+    let mut key = String::new();
+    reader
+        .read_to_string(&mut key)
+        .map_err(|e| PullError::InvalidConfig(format!("Cannot read API key from {source}: {e}")))?;
+
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(PullError::InvalidConfig(format!(
+            "API key from {source} is empty"
+        )));
+    }
+    Ok(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_api_key_file() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("api-key");
+        std::fs::write(&path, "  secret-key\n")?;
+        assert_eq!(read_api_key_file(&path)?, "secret-key");
+
+        std::fs::write(&path, "\n")?;
+        assert!(matches!(
+            read_api_key_file(&path),
+            Err(PullError::InvalidConfig(message)) if message.contains("is empty")
+        ));
+
+        assert!(matches!(
+            read_api_key_file(&dir.path().join("missing")),
+            Err(PullError::InvalidConfig(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_api_key_takes_precedence() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let config = Config {
+            api_key: Some(read_api_key("from-stdin\n".as_bytes(), "stdin")?),
+            forem_api_key: Some("from-env".to_string()),
+            ghost_content_api_key: None,
+            bluesky_app_password: None,
+            writefreely_access_token: None,
+            user_agent: None,
+            per_page: None,
+            forem_api_version: None,
+            output_dir: None,
+            username: None,
+        };
+        assert_eq!(config.forem_api_key()?, "from-stdin");
+        assert_eq!(config.ghost_content_api_key()?, "from-stdin");
+        assert_eq!(config.bluesky_app_password()?, "from-stdin");
+        assert_eq!(config.writefreely_access_token()?, "from-stdin");
+        Ok(())
+    }
 }
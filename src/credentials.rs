@@ -0,0 +1,97 @@
+//! API keys kept in the OS credential store, for `puller login` and `puller logout`.
+//!
+//! Keys are stored under the `puller` service with the platform string as the
+//! account, so `devto` and `forem:open` each get their own entry. Without the
+//! `keyring` feature nothing is stored and lookups find no key.
+
+use crate::error::{PullError, Result};
+use crate::platform::Platform;
+
+/// Service name the keys are stored under.
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "puller";
+
+#[cfg(feature = "keyring")]
+fn entry(platform: &Platform) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, &platform.to_string()).map_err(keyring_error)
+}
+
+#[cfg(feature = "keyring")]
+#[allow(clippy::needless_pass_by_value)]
+fn keyring_error(e: keyring::Error) -> PullError {
+    PullError::Keyring(e.to_string())
+}
+
+/// Returns the stored key for `platform`, if there is one.
+///
+/// A store that can't be reached is logged and treated as empty, so the key
+/// can still come from the environment.
+#[cfg(feature = "keyring")]
+pub fn load(platform: &Platform) -> Result<Option<String>> {
+    match entry(platform)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => {
+            tracing::warn!(%platform, "cannot read the credential store: {e}");
+            Ok(None)
+        }
+    }
+}
+
+/// Stores `key` for `platform`, replacing any key stored before.
+#[cfg(feature = "keyring")]
+pub fn store(platform: &Platform, key: &str) -> Result<()> {
+    entry(platform)?.set_password(key).map_err(keyring_error)
+}
+
+/// Removes the stored key for `platform`, returning whether there was one.
+#[cfg(feature = "keyring")]
+pub fn delete(platform: &Platform) -> Result<bool> {
+    match entry(platform)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+#[allow(clippy::unnecessary_wraps)]
+pub fn load(_platform: &Platform) -> Result<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store(_platform: &Platform, _key: &str) -> Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn delete(_platform: &Platform) -> Result<bool> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn unsupported() -> PullError {
+    PullError::Keyring(
+        "puller was built without keyring support, rebuild with --features keyring".to_string(),
+    )
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "keyring"))]
+mod tests {
+    use super::*;
+    use crate::forem::ForemInstance;
+
+    #[test]
+    fn test_without_keyring_feature() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform = Platform::Forem(ForemInstance::DevTo);
+        assert_eq!(load(&platform)?, None);
+        assert!(matches!(
+            store(&platform, "key"),
+            Err(PullError::Keyring(_))
+        ));
+        assert!(matches!(delete(&platform), Err(PullError::Keyring(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+//! Parsing of `--since` cutoffs, absolute (`2024-03-15`, `2024-03-15T14:00:00Z`) or
+//! relative (`7d`, `yesterday`).
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+
+use crate::error::{PullError, Result};
+
+/// A `--since` cutoff: a whole day, or an exact instant when a time was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    /// Articles published on this day (UTC) or later pass
+    Date(NaiveDate),
+    /// Articles published at this instant or later pass
+    DateTime(DateTime<Utc>),
+}
+
+impl Since {
+    /// Returns true when an article published at `published_at` passes the cutoff.
+    pub fn admits(self, published_at: DateTime<Utc>) -> bool {
+        match self {
+            Self::Date(date) => published_at.date_naive() >= date,
+            Self::DateTime(instant) => published_at >= instant,
+        }
+    }
+}
+
+impl FromStr for Since {
+    type Err = PullError;
+
+    /// Parses an absolute cutoff, `YYYY-MM-DD` or an RFC 3339 date and time.
+    fn from_str(s: &str) -> Result<Self> {
+        parse_absolute(s.trim()).ok_or_else(|| {
+            PullError::InvalidDate(format!(
+                "Expected YYYY-MM-DD or an RFC 3339 date and time, got: {s}"
+            ))
+        })
+    }
+}
+
+fn parse_absolute(input: &str) -> Option<Since> {
+    if let Ok(instant) = DateTime::parse_from_rfc3339(input) {
+        return Some(Since::DateTime(instant.with_timezone(&Utc)));
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .map(Since::Date)
+}
+
+/// Parses a `--since` value relative to the current UTC date.
+pub fn parse_since(s: &str) -> Result<Since> {
+    parse_since_from(s, Utc::now().date_naive())
+}
+
+/// Parses a `--since` value relative to `today`.
+///
+/// Accepts `YYYY-MM-DD`, an RFC 3339 date and time such as `2024-03-15T14:00:00Z`,
+/// `today`, `yesterday`, or a count followed by a unit: `d` (days), `w` (weeks),
+/// `m` (months) or `y` (years), e.g. `7d` or `3m`. Only the RFC 3339 form gives
+/// a cutoff finer than a day.
+pub fn parse_since_from(s: &str, today: NaiveDate) -> Result<Since> {
+    if let Some(since) = parse_absolute(s.trim()) {
+        return Ok(since);
+    }
+
+    let input = s.trim().to_lowercase();
+    let invalid = || {
+        PullError::InvalidDate(format!(
+            "Expected YYYY-MM-DD, a date and time like 2024-03-15T14:00:00Z, today, yesterday \
+             or a relative date like 7d, 2w, 3m, 1y, got: {s}"
+        ))
+    };
+
+    match input.as_str() {
+        "today" => return Ok(Since::Date(today)),
+        "yesterday" => {
+            return today
+                .checked_sub_days(Days::new(1))
+                .map(Since::Date)
+                .ok_or_else(invalid)
+        }
+        _ => {}
+    }
+
+    let unit_start = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = input.split_at(unit_start);
+    let amount: u32 = amount.parse().map_err(|_| invalid())?;
+
+    let date = match unit {
+        "d" => today.checked_sub_days(Days::new(u64::from(amount))),
+        "w" => today.checked_sub_days(Days::new(u64::from(amount) * 7)),
+        "m" => today.checked_sub_months(Months::new(amount)),
+        "y" => amount
+            .checked_mul(12)
+            .and_then(|months| today.checked_sub_months(Months::new(months))),
+        _ => None,
+    };
+    date.map(Since::Date).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestResult = std::result::Result<(), Box<dyn std::error::Error>>;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).expect("valid test date")
+    }
+
+    fn today() -> NaiveDate {
+        ymd(2024, 3, 15)
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> Since {
+        Since::Date(ymd(y, m, d))
+    }
+
+    #[test]
+    fn test_absolute_date() -> TestResult {
+        assert_eq!(parse_since_from("2024-01-02", today())?, day(2024, 1, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_today_and_yesterday() -> TestResult {
+        assert_eq!(parse_since_from("today", today())?, day(2024, 3, 15));
+        assert_eq!(parse_since_from("Yesterday", today())?, day(2024, 3, 14));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_days() -> TestResult {
+        assert_eq!(parse_since_from("7d", today())?, day(2024, 3, 8));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_weeks() -> TestResult {
+        assert_eq!(parse_since_from("2w", today())?, day(2024, 3, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_months() -> TestResult {
+        assert_eq!(parse_since_from("3m", today())?, day(2023, 12, 15));
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_years() -> TestResult {
+        assert_eq!(parse_since_from("1y", today())?, day(2023, 3, 15));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for input in [
+            "",
+            "d",
+            "7",
+            "7x",
+            "-3d",
+            "15/03/2024",
+            "soon",
+            "2024-03-15T14:00",
+        ] {
+            assert!(
+                matches!(
+                    parse_since_from(input, today()),
+                    Err(PullError::InvalidDate(_))
+                ),
+                "{input} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_datetime() -> TestResult {
+        assert_eq!(
+            parse_since_from("2024-03-15T14:00:00Z", today())?,
+            Since::DateTime("2024-03-15T14:00:00Z".parse()?)
+        );
+        // Other offsets are converted to UTC
+        assert_eq!(
+            parse_since_from(" 2024-03-15T16:00:00+02:00 ", today())?,
+            Since::DateTime("2024-03-15T14:00:00Z".parse()?)
+        );
+        assert_eq!("2024-03-15".parse::<Since>()?, day(2024, 3, 15));
+        assert!("7d".parse::<Since>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_admits_date_and_datetime() -> TestResult {
+        let morning: DateTime<Utc> = "2024-03-15T09:00:00Z".parse()?;
+        let afternoon: DateTime<Utc> = "2024-03-15T15:00:00Z".parse()?;
+        let day_before: DateTime<Utc> = "2024-03-14T23:59:59Z".parse()?;
+
+        // A date admits the whole day, whatever the time
+        let date = day(2024, 3, 15);
+        assert!(date.admits(morning));
+        assert!(date.admits(afternoon));
+        assert!(!date.admits(day_before));
+
+        // A date and time cuts the day in two
+        let instant = parse_since_from("2024-03-15T14:00:00Z", today())?;
+        assert!(!instant.admits(morning));
+        assert!(instant.admits(afternoon));
+        assert!(instant.admits("2024-03-15T14:00:00Z".parse()?));
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+//! EPUB export for `puller pull --epub`, one chapter per article.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use epub_builder::{EpubBuilder, EpubContent, EpubVersion, ReferenceType, ZipLibrary};
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use reqwest::header::CONTENT_TYPE;
+use tracing::warn;
+
+use crate::article::PulledArticle;
+use crate::combine::{date_label, sort_by_date};
+use crate::error::{PullError, Result};
+use crate::html::html_to_markdown;
+
+/// A downloaded cover image, see `download_cover`.
+pub struct CoverImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// Downloads an article's cover image, logging and skipping it when the
+/// download fails or isn't an image.
+pub async fn download_cover(
+    client: &reqwest::Client,
+    article: &PulledArticle,
+) -> Option<CoverImage> {
+    let url = article.cover_image.as_ref()?;
+    let result = async {
+        let response = client.get(url.clone()).send().await?.error_for_status()?;
+        let mime_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_string());
+        let bytes = response.bytes().await?.to_vec();
+        Ok::<_, PullError>((mime_type, bytes))
+    }
+    .await;
+
+    match result {
+        Ok((Some(mime_type), bytes)) if mime_type.starts_with("image/") => {
+            Some(CoverImage { bytes, mime_type })
+        }
+        Ok(_) => {
+            warn!(%url, "cover image is not an image, leaving it out");
+            None
+        }
+        Err(e) => {
+            warn!(%url, "could not download cover image, leaving it out: {e}");
+            None
+        }
+    }
+}
+
+/// Writes `articles` as an EPUB 3 book to `output`, oldest first.
+///
+/// `covers` holds the downloaded cover of each article by platform id; articles
+/// without one simply start with their title.
+pub fn write_epub(
+    articles: &mut [PulledArticle],
+    covers: &HashMap<String, CoverImage>,
+    output: impl Write,
+) -> Result<()> {
+    sort_by_date(articles);
+
+    let mut builder =
+        EpubBuilder::new(ZipLibrary::new().map_err(epub_error)?).map_err(epub_error)?;
+    builder.epub_version(EpubVersion::V30);
+    builder.metadata("title", "Articles").map_err(epub_error)?;
+    if let Some(author) = articles
+        .iter()
+        .find_map(|article| article.author.as_deref())
+    {
+        builder.metadata("author", author).map_err(epub_error)?;
+    }
+    builder
+        .metadata("generator", "puller")
+        .map_err(epub_error)?;
+    builder.inline_toc();
+
+    for (index, article) in articles.iter().enumerate() {
+        let cover_path = covers.get(&article.platform_id).map(|cover| {
+            let path = format!("images/cover-{}.{}", index + 1, extension(&cover.mime_type));
+            (path, cover)
+        });
+        if let Some((path, cover)) = &cover_path {
+            builder
+                .add_resource(path, cover.bytes.as_slice(), cover.mime_type.as_str())
+                .map_err(epub_error)?;
+        }
+
+        let chapter = chapter_xhtml(article, cover_path.as_ref().map(|(path, _)| path.as_str()));
+        builder
+            .add_content(
+                EpubContent::new(format!("chapter-{}.xhtml", index + 1), chapter.as_bytes())
+                    .title(&article.title)
+                    .reftype(ReferenceType::Text),
+            )
+            .map_err(epub_error)?;
+    }
+
+    builder.generate(output).map_err(epub_error)?;
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn epub_error(e: impl ToString) -> PullError {
+    PullError::Epub(e.to_string())
+}
+
+fn extension(mime_type: &str) -> &str {
+    match mime_type {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        other => other.strip_prefix("image/").unwrap_or("img"),
+    }
+}
+
+fn chapter_xhtml(article: &PulledArticle, cover_path: Option<&str>) -> String {
+    let title = escape(&article.title);
+    let mut body = String::new();
+    writeln!(body, "<h1>{title}</h1>").expect("String write failed");
+    writeln!(body, "<p><em>{}</em></p>", escape(&date_label(article)))
+        .expect("String write failed");
+    if let Some(cover_path) = cover_path {
+        writeln!(body, "<p><img src=\"{cover_path}\" alt=\"\"/></p>").expect("String write failed");
+    }
+    body.push_str(&markdown_to_html(&article.body_markdown));
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+         <head><title>{title}</title></head>\n\
+         <body>\n{body}</body>\n\
+         </html>\n"
+    )
+}
+
+/// Renders an article body as XHTML. Images in the body stay on the web, which an
+/// EPUB may not load, so they become links to the image instead.
+///
+/// Raw HTML such as `<br>` is rarely valid XHTML, and strict readers reject the
+/// whole book over it: it is converted to markdown first, and what is left is
+/// escaped into text.
+fn markdown_to_html(markdown: &str) -> String {
+    let markdown = html_to_markdown(markdown);
+    let events = Parser::new_ext(
+        &markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    )
+    .map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }),
+        Event::End(TagEnd::Image) => Event::End(TagEnd::Link),
+        event => event,
+    });
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    output
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::article::ArticleStats;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+
+    fn article(id: &str, title: &str, body: &str) -> PulledArticle {
+        PulledArticle {
+            platform_id: id.to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: title.to_string(),
+            body_markdown: body.to_string(),
+            published_at: "2024-03-15T10:00:00Z".parse().ok(),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: Some("alice".to_string()),
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        }
+    }
+
+    #[test]
+    fn test_chapter_xhtml() {
+        let article = article(
+            "1",
+            "Tips & <Tricks>",
+            "Intro\n\n![diagram](https://cdn.example.com/diagram.png)\n",
+        );
+
+        let chapter = chapter_xhtml(&article, Some("images/cover-1.png"));
+        assert!(chapter.contains("<title>Tips &amp; &lt;Tricks&gt;</title>"));
+        assert!(chapter.contains("<p><em>2024-03-15</em></p>"));
+        assert!(chapter.contains("<img src=\"images/cover-1.png\" alt=\"\"/>"));
+        assert!(chapter.contains("<a href=\"https://cdn.example.com/diagram.png\">diagram</a>"));
+        assert!(!chapter.contains("<img src=\"https://"));
+
+        assert!(!chapter_xhtml(&article, None).contains("<img"));
+    }
+
+    #[test]
+    fn test_chapter_xhtml_without_raw_html() {
+        let article = article(
+            "1",
+            "Raw",
+            "Line one<br>\nLine two <span class=\"x\">\n\n<iframe src=\"https://example.com/embed\">\n",
+        );
+
+        let chapter = chapter_xhtml(&article, None);
+        assert!(chapter.contains("Line one"));
+        assert!(chapter.contains("Line two"));
+        assert!(!chapter.contains("<br>"));
+        assert!(!chapter.contains("<span"));
+        assert!(!chapter.contains("<iframe"));
+    }
+
+    #[test]
+    fn test_write_epub() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut articles = vec![
+            article("1", "First", "Hello"),
+            article("2", "Second", "World"),
+        ];
+        let covers = HashMap::from([(
+            "2".to_string(),
+            CoverImage {
+                bytes: b"\x89PNG".to_vec(),
+                mime_type: "image/png".to_string(),
+            },
+        )]);
+
+        let mut epub = Vec::new();
+        write_epub(&mut articles, &covers, &mut epub)?;
+
+        assert!(epub.starts_with(b"PK"));
+        assert!(epub
+            .windows(b"application/epub+zip".len())
+            .any(|window| window == b"application/epub+zip"));
+        Ok(())
+    }
+}
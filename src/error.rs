@@ -1,12 +1,27 @@
+use serde::Deserialize;
 use thiserror::Error;
 
+use crate::redact::redact;
+
 #[derive(Error, Debug)]
 pub enum PullError {
-    #[error("API error: {0}")]
-    Api(String),
+    /// The platform answered with an error status, see `PullError::api`
+    #[error("API error {status}: {message}")]
+    Api { status: u16, message: String },
 
+    /// Built through `From`, which masks API keys in the request URL
     #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+
+    /// A transport or API error with the request it happened on, see `RequestContext`
+    #[error("{operation} failed ({url}): {source}")]
+    Request {
+        /// What the request was for, e.g. "list page 3" or "fetch article 123"
+        operation: String,
+        /// Request URL with API keys masked
+        url: String,
+        source: Box<PullError>,
+    },
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
@@ -14,12 +29,28 @@ pub enum PullError {
     #[error("YAML serialization error: {0}")]
     Yaml(#[from] serde_yaml::Error),
 
+    /// An article that could not be rendered to markdown, so no file was written
+    #[error("Cannot render article {title:?}: {source}")]
+    Render {
+        title: String,
+        source: Box<PullError>,
+    },
+
+    #[error("Feed parsing error: {0}")]
+    Feed(#[from] feed_rs::parser::ParseFeedError),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Invalid or missing API key for {0}")]
+    Unauthorized(String),
+
     #[error("Missing configuration: {0}")]
     MissingConfig(String),
 
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
     #[error("Invalid date format: {0}")]
     InvalidDate(String),
 
@@ -31,6 +62,254 @@ pub enum PullError {
 
     #[error("Unsupported platform: {0}")]
     UnsupportedPlatform(String),
+
+    #[error("{0} article(s) failed to pull")]
+    Incomplete(usize),
+
+    #[error("{0} discrepancy(ies) found in the archive")]
+    Unverified(usize),
+
+    #[error("Credential store error: {0}")]
+    Keyring(String),
+
+    #[error("EPUB error: {0}")]
+    Epub(String),
+}
+
+/// Exit code for errors without a more specific one.
+pub const EXIT_FAILURE: u8 = 1;
+// 2 is left to clap, which exits with it on invalid arguments
+/// Exit code when the platform rejected the API key.
+pub const EXIT_UNAUTHORIZED: u8 = 3;
+/// Exit code when the platform rate limited us.
+pub const EXIT_RATE_LIMITED: u8 = 4;
+/// Exit code when an article, feed or site doesn't exist.
+pub const EXIT_NOT_FOUND: u8 = 5;
+/// Exit code when reading or writing local files failed.
+pub const EXIT_IO: u8 = 6;
+
+impl PullError {
+    /// Returns the process exit code for this error, so scripts can tell failures apart.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Unauthorized(_) => EXIT_UNAUTHORIZED,
+            Self::RateLimited(_) => EXIT_RATE_LIMITED,
+            Self::NotFound(_) => EXIT_NOT_FOUND,
+            Self::Io(_) => EXIT_IO,
+            Self::Request { source, .. } => source.exit_code(),
+            Self::Api { .. } | Self::Http(_) => match self.status() {
+                Some(401 | 403) => EXIT_UNAUTHORIZED,
+                Some(429) => EXIT_RATE_LIMITED,
+                Some(404) => EXIT_NOT_FOUND,
+                _ => EXIT_FAILURE,
+            },
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+impl PullError {
+    /// Returns the HTTP status of an `Api` or `Http` error, looking through `Request`.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Http(e) => e.status().map(|status| status.as_u16()),
+            Self::Request { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Builds an `Api` error from an error response, taking the message out of
+    /// the JSON body when it has a known shape and keeping the raw body otherwise.
+    /// API keys in the message are masked.
+    pub fn api(status: u16, body: &str) -> Self {
+        let body = body.trim();
+        let message = serde_json::from_str::<ErrorBody>(body)
+            .ok()
+            .and_then(ErrorBody::into_message)
+            .unwrap_or_else(|| body.to_string());
+        Self::Api {
+            status,
+            message: redact(&message),
+        }
+    }
+}
+
+/// Error bodies sent by the supported platforms:
+///
+/// - Forem: `{"error": "not found", "status": 404}`
+/// - Ghost: `{"errors": [{"message": "...", "type": "NotFoundError"}]}`
+/// - WriteFreely: `{"code": 404, "error_msg": "..."}`
+/// - Bluesky: `{"error": "InvalidRequest", "message": "..."}`
+#[derive(Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    error_msg: Option<String>,
+    #[serde(default)]
+    errors: Vec<ErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+impl ErrorBody {
+    fn into_message(self) -> Option<String> {
+        let message = match (self.error, self.message) {
+            (Some(error), Some(message)) => Some(format!("{error}: {message}")),
+            (error, message) => error.or(message),
+        };
+        message
+            .or(self.error_msg)
+            .or_else(|| self.errors.into_iter().next().map(|detail| detail.message))
+            .filter(|message| !message.is_empty())
+    }
+}
+
+impl From<reqwest::Error> for PullError {
+    fn from(mut error: reqwest::Error) -> Self {
+        // reqwest includes the request URL in both Display and Debug output
+        if let Some(url) = error.url_mut() {
+            if let Some(query) = url.query() {
+                let redacted = redact(query);
+                url.set_query(Some(&redacted));
+            }
+        }
+        Self::Http(error)
+    }
 }
 
 pub type Result<T> = std::result::Result<T, PullError>;
+
+/// Attaches the failing request to transport and API errors, `anyhow`-style.
+pub trait RequestContext<T> {
+    /// Wraps an `Http` or `Api` error in `PullError::Request`. Other errors
+    /// already say what went wrong and are passed through.
+    fn context(self, operation: impl Into<String>, url: &str) -> Result<T>;
+}
+
+impl<T> RequestContext<T> for Result<T> {
+    fn context(self, operation: impl Into<String>, url: &str) -> Self {
+        self.map_err(|error| match error {
+            PullError::Http(_) | PullError::Api { .. } => PullError::Request {
+                operation: operation.into(),
+                url: redact(url),
+                source: Box::new(error),
+            },
+            error => error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_http_error_never_shows_api_key() {
+        // Nothing listens on port 1, so the request fails with the URL attached
+        let error = reqwest::Client::new()
+            .get("http://127.0.0.1:1/api/articles?api_key=s3cr3t-key&page=1")
+            .send()
+            .await
+            .expect_err("connection should be refused");
+
+        let error = PullError::from(error);
+        assert!(!error.to_string().contains("s3cr3t-key"));
+        assert!(!format!("{error:?}").contains("s3cr3t-key"));
+        assert!(error.to_string().contains("api_key=***"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(
+            PullError::Unauthorized("devto".to_string()).exit_code(),
+            EXIT_UNAUTHORIZED
+        );
+        assert_eq!(PullError::RateLimited(60).exit_code(), EXIT_RATE_LIMITED);
+        assert_eq!(
+            PullError::NotFound("123".to_string()).exit_code(),
+            EXIT_NOT_FOUND
+        );
+        assert_eq!(
+            PullError::Io(std::io::Error::other("disk full")).exit_code(),
+            EXIT_IO
+        );
+        assert_eq!(PullError::api(500, "").exit_code(), EXIT_FAILURE);
+        assert_eq!(PullError::api(401, "").exit_code(), EXIT_UNAUTHORIZED);
+        assert_eq!(PullError::api(403, "").exit_code(), EXIT_UNAUTHORIZED);
+        assert_eq!(PullError::api(429, "").exit_code(), EXIT_RATE_LIMITED);
+        assert_eq!(PullError::api(404, "").exit_code(), EXIT_NOT_FOUND);
+        assert_eq!(PullError::Incomplete(2).exit_code(), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_request_context() {
+        let result: Result<()> = Err(PullError::api(502, "502 Bad Gateway"));
+        let error = result
+            .context(
+                "list page 3",
+                "https://example.com/api?page=3&api_key=s3cr3t-key",
+            )
+            .expect_err("context keeps the error");
+
+        let message = error.to_string();
+        assert!(message.starts_with("list page 3 failed"));
+        assert!(message.contains("502 Bad Gateway"));
+        assert!(!message.contains("s3cr3t-key"));
+        assert_eq!(error.exit_code(), EXIT_FAILURE);
+
+        let result: Result<()> = Err(PullError::RateLimited(60));
+        let error = result
+            .context("fetch article 123", "https://example.com/api/articles/123")
+            .expect_err("context keeps the error");
+        assert!(matches!(error, PullError::RateLimited(60)));
+    }
+
+    #[test]
+    fn test_api_error_from_json_body() {
+        let forem = PullError::api(422, r#"{"error": "Title can't be blank", "status": 422}"#);
+        assert!(matches!(
+            &forem,
+            PullError::Api { status: 422, message } if message == "Title can't be blank"
+        ));
+        assert_eq!(forem.to_string(), "API error 422: Title can't be blank");
+
+        let ghost = PullError::api(
+            404,
+            r#"{"errors": [{"message": "Resource not found", "type": "NotFoundError"}]}"#,
+        );
+        assert!(matches!(ghost, PullError::Api { message, .. } if message == "Resource not found"));
+
+        let bluesky = PullError::api(
+            400,
+            r#"{"error": "InvalidRequest", "message": "Missing rkey"}"#,
+        );
+        assert!(matches!(
+            bluesky,
+            PullError::Api { message, .. } if message == "InvalidRequest: Missing rkey"
+        ));
+    }
+
+    #[test]
+    fn test_api_error_from_plain_body() {
+        let error = PullError::api(502, "<html>Bad Gateway</html>\n");
+        assert!(matches!(
+            &error,
+            PullError::Api { status: 502, message } if message == "<html>Bad Gateway</html>"
+        ));
+
+        // JSON without a known message field is kept whole, with keys masked
+        let error = PullError::api(500, r#"{"detail": "oops", "api_key": "s3cr3t-key"}"#);
+        let PullError::Api { message, .. } = error else {
+            panic!("expected an API error");
+        };
+        assert!(message.contains("oops"));
+        assert!(!message.contains("s3cr3t-key"));
+    }
+}
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use url::Url;
 
 use crate::error::PullError;
 
@@ -53,6 +54,29 @@ pub enum ForemInstance {
 }
 
 impl ForemInstance {
+    /// Returns every known Forem instance (everything except `Custom`).
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::DevTo,
+            Self::Vibe,
+            Self::Open,
+            Self::Future,
+            Self::Gg,
+            Self::Music,
+            Self::Popcorn,
+            Self::Design,
+            Self::Zeroday,
+            Self::Golf,
+            Self::Crypto,
+            Self::Parenting,
+            Self::Core,
+            Self::Maker,
+            Self::Hmpljs,
+            Self::DumbDev,
+        ]
+    }
+
     /// Returns the API base URL for this Forem instance.
     #[must_use]
     pub fn base_url(&self) -> String {
@@ -101,6 +125,51 @@ impl ForemInstance {
         }
     }
 
+    /// Detects the Forem instance from an article or profile URL's host.
+    ///
+    /// Known hosts map to their variant; any other host (an unlisted `*.forem.com`
+    /// community or a self-hosted instance) becomes `Custom`. Returns `None` only
+    /// for URLs without a host.
+    #[must_use]
+    pub fn from_url(url: &Url) -> Option<Self> {
+        let host = url.host_str()?.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+
+        if let Some(instance) = Self::from_name(host) {
+            return Some(instance);
+        }
+
+        let domain = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        Some(Self::Custom { domain })
+    }
+
+    /// Looks up a known instance by short name or host, e.g. "vibe" or "vibe.forem.com".
+    fn from_name(name: &str) -> Option<Self> {
+        let instance = match name {
+            "devto" | "dev.to" | "dev" => Self::DevTo,
+            "vibe" | "vibe.forem" | "vibe.forem.com" | "vibeforem" => Self::Vibe,
+            "open" | "open.forem" | "open.forem.com" => Self::Open,
+            "future" | "future.forem" | "future.forem.com" => Self::Future,
+            "gg" | "gg.forem" | "gg.forem.com" => Self::Gg,
+            "music" | "music.forem" | "music.forem.com" => Self::Music,
+            "popcorn" | "popcorn.forem" | "popcorn.forem.com" => Self::Popcorn,
+            "design" | "design.forem" | "design.forem.com" => Self::Design,
+            "zeroday" | "zeroday.forem" | "zeroday.forem.com" => Self::Zeroday,
+            "golf" | "golf.forem" | "golf.forem.com" => Self::Golf,
+            "crypto" | "crypto.forem" | "crypto.forem.com" => Self::Crypto,
+            "parenting" | "parenting.forem" | "parenting.forem.com" => Self::Parenting,
+            "core" | "core.forem" | "core.forem.com" => Self::Core,
+            "maker" | "maker.forem" | "maker.forem.com" => Self::Maker,
+            "hmpljs" | "hmpljs.forem" | "hmpljs.forem.com" => Self::Hmpljs,
+            "dumbdev" | "dumb.dev" | "dumb.dev.to" => Self::DumbDev,
+            _ => return None,
+        };
+        Some(instance)
+    }
+
     /// Returns the short identifier for this instance (used in platform strings).
     #[must_use]
     pub fn as_str(&self) -> String {
@@ -143,6 +212,8 @@ impl FromStr for ForemInstance {
     /// - "open" -> Open
     /// - ... (other known instances)
     /// - "custom:example.com" -> Custom { domain: "example.com" }
+    /// - a host or URL such as "https://dev.to/username" -> the instance for its
+    ///   host, or Custom for an unknown host
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lower = s.to_lowercase();
 
@@ -158,27 +229,21 @@ impl FromStr for ForemInstance {
             });
         }
 
-        match lower.as_str() {
-            "devto" | "dev.to" | "dev" => Ok(Self::DevTo),
-            "vibe" | "vibe.forem" | "vibe.forem.com" | "vibeforem" => Ok(Self::Vibe),
-            "open" | "open.forem" | "open.forem.com" => Ok(Self::Open),
-            "future" | "future.forem" | "future.forem.com" => Ok(Self::Future),
-            "gg" | "gg.forem" | "gg.forem.com" => Ok(Self::Gg),
-            "music" | "music.forem" | "music.forem.com" => Ok(Self::Music),
-            "popcorn" | "popcorn.forem" | "popcorn.forem.com" => Ok(Self::Popcorn),
-            "design" | "design.forem" | "design.forem.com" => Ok(Self::Design),
-            "zeroday" | "zeroday.forem" | "zeroday.forem.com" => Ok(Self::Zeroday),
-            "golf" | "golf.forem" | "golf.forem.com" => Ok(Self::Golf),
-            "crypto" | "crypto.forem" | "crypto.forem.com" => Ok(Self::Crypto),
-            "parenting" | "parenting.forem" | "parenting.forem.com" => Ok(Self::Parenting),
-            "core" | "core.forem" | "core.forem.com" => Ok(Self::Core),
-            "maker" | "maker.forem" | "maker.forem.com" => Ok(Self::Maker),
-            "hmpljs" | "hmpljs.forem" | "hmpljs.forem.com" => Ok(Self::Hmpljs),
-            "dumbdev" | "dumb.dev" | "dumb.dev.to" => Ok(Self::DumbDev),
-            _ => Err(PullError::UnsupportedPlatform(format!(
-                "Unknown Forem instance: {s}"
-            ))),
+        if let Some(instance) = Self::from_name(&lower) {
+            return Ok(instance);
         }
+
+        // A pasted host or URL, e.g. "mycompany.forem.com" or "https://dev.to/username"
+        let url = if lower.contains("://") {
+            Url::parse(&lower).ok()
+        } else if lower.contains('.') {
+            Url::parse(&format!("https://{lower}")).ok()
+        } else {
+            None
+        };
+        url.as_ref()
+            .and_then(Self::from_url)
+            .ok_or_else(|| PullError::UnsupportedPlatform(format!("Unknown Forem instance: {s}")))
     }
 }
 
@@ -339,6 +404,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_url_devto() -> Result<(), url::ParseError> {
+        let url = Url::parse("https://dev.to/user/my-post-abc")?;
+        assert_eq!(ForemInstance::from_url(&url), Some(ForemInstance::DevTo));
+
+        let url = Url::parse("https://www.dev.to/user")?;
+        assert_eq!(ForemInstance::from_url(&url), Some(ForemInstance::DevTo));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_url_forem_subdomain() -> Result<(), url::ParseError> {
+        let url = Url::parse("https://golf.forem.com/user/post")?;
+        assert_eq!(ForemInstance::from_url(&url), Some(ForemInstance::Golf));
+
+        let url = Url::parse("https://newcommunity.forem.com/user/post")?;
+        assert_eq!(
+            ForemInstance::from_url(&url),
+            Some(ForemInstance::Custom {
+                domain: "newcommunity.forem.com".to_string()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_url_self_hosted() -> Result<(), url::ParseError> {
+        let url = Url::parse("https://community.example.com/user/post")?;
+        assert_eq!(
+            ForemInstance::from_url(&url),
+            Some(ForemInstance::Custom {
+                domain: "community.example.com".to_string()
+            })
+        );
+
+        let url = Url::parse("https://forem.internal:8443/user/post")?;
+        assert_eq!(
+            ForemInstance::from_url(&url),
+            Some(ForemInstance::Custom {
+                domain: "forem.internal:8443".to_string()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_all_excludes_custom_and_roundtrips() {
+        let all = ForemInstance::all();
+        assert_eq!(all.len(), 16);
+        for instance in all {
+            assert!(!matches!(instance, ForemInstance::Custom { .. }));
+            assert_eq!(
+                instance.as_str().parse::<ForemInstance>().unwrap(),
+                instance
+            );
+        }
+    }
+
     #[test]
     fn test_display_name() {
         assert_eq!(ForemInstance::DevTo.display_name(), "Dev.to");
@@ -351,4 +474,53 @@ mod tests {
             "Forem (example.com)"
         );
     }
+
+    #[test]
+    fn test_from_str_host() -> Result<(), PullError> {
+        assert_eq!(
+            "golf.forem.com".parse::<ForemInstance>()?,
+            ForemInstance::Golf
+        );
+        assert_eq!(
+            "mycompany.forem.com".parse::<ForemInstance>()?,
+            ForemInstance::Custom {
+                domain: "mycompany.forem.com".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_url_with_path() -> Result<(), PullError> {
+        assert_eq!(
+            "https://dev.to/username".parse::<ForemInstance>()?,
+            ForemInstance::DevTo
+        );
+        assert_eq!(
+            "dev.to/username".parse::<ForemInstance>()?,
+            ForemInstance::DevTo
+        );
+        assert_eq!(
+            "https://community.example.com:8443/user/post".parse::<ForemInstance>()?,
+            ForemInstance::Custom {
+                domain: "community.example.com:8443".to_string()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_trailing_slash() -> Result<(), PullError> {
+        assert_eq!(
+            "https://mycompany.forem.com/".parse::<ForemInstance>()?,
+            ForemInstance::Custom {
+                domain: "mycompany.forem.com".to_string()
+            }
+        );
+        assert_eq!(
+            "vibe.forem.com/".parse::<ForemInstance>()?,
+            ForemInstance::Vibe
+        );
+        Ok(())
+    }
 }
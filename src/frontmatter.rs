@@ -0,0 +1,346 @@
+//! Frontmatter styles an article can be written with, one `FrontmatterWriter` each.
+//!
+//! `PulledArticle::to_markdown_as` wraps whatever a writer renders in `---` fences,
+//! followed by the `# Platform ID` tracking comment.
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::article::{slugify, ArticleStats, Organization, PulledArticle, DEFAULT_MAX_SLUG_LEN};
+use crate::error::Result;
+
+/// Renders the frontmatter of an article in one style.
+pub trait FrontmatterWriter: Sync {
+    /// Returns the frontmatter keys of `article` as YAML, without the `---` fences.
+    fn render(&self, article: &PulledArticle, options: &FrontmatterOptions) -> Result<String>;
+}
+
+/// What goes into the frontmatter besides the keys of its style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrontmatterOptions {
+    /// End with the `# Platform ID` comment that ties the file to its article
+    pub tracking_marker: bool,
+    /// Write the article's platform id as an `id` key, for scripts. Forem's own
+    /// format has no such key and leaves it out.
+    pub include_id: bool,
+    /// Maximum length of a `slug` generated from the title, as in the filename
+    pub max_slug_len: usize,
+}
+
+impl Default for FrontmatterOptions {
+    fn default() -> Self {
+        Self {
+            tracking_marker: true,
+            include_id: false,
+            max_slug_len: DEFAULT_MAX_SLUG_LEN,
+        }
+    }
+}
+
+/// Which frontmatter keys an article is written with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FrontmatterFormat {
+    /// `status: publish|draft`, `updated`, and `scheduled_at` for scheduled drafts
+    #[default]
+    Standard,
+    /// Hugo's `draft: true|false` and `lastmod`; scheduled drafts keep their `date`,
+    /// which Hugo leaves unpublished until it passes
+    Hugo,
+    /// Only the keys dev.to's editor and GitHub sync read, so the file can be
+    /// published again as is: `published: true|false` and comma-separated `tags`
+    Forem,
+    /// Jekyll's `layout: post`, with the series as the only entry of `categories`
+    Jekyll,
+}
+
+impl FrontmatterFormat {
+    /// Returns the writer rendering this style.
+    pub fn writer(self) -> &'static dyn FrontmatterWriter {
+        match self {
+            Self::Standard => &StandardWriter,
+            Self::Hugo => &HugoWriter,
+            Self::Forem => &ForemWriter,
+            Self::Jekyll => &JekyllWriter,
+        }
+    }
+}
+
+/// Writes `FrontmatterFormat::Standard`.
+pub struct StandardWriter;
+
+/// Writes `FrontmatterFormat::Hugo`.
+pub struct HugoWriter;
+
+/// Writes `FrontmatterFormat::Forem`.
+pub struct ForemWriter;
+
+/// Writes `FrontmatterFormat::Jekyll`.
+pub struct JekyllWriter;
+
+/// Keys of the standard and Hugo styles, which differ only in how they mark
+/// drafts, edits and scheduled posts.
+#[derive(Debug, Serialize, Deserialize)]
+struct Frontmatter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    title: String,
+    /// Slug of the original URL, so a static site can keep the same permalinks
+    slug: String,
+    /// Publication date of an already-published article
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<DateTime<Utc>>,
+    /// Future publication date of a scheduled draft
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scheduled_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lastmod: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    series: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cover_image: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization: Option<Organization>,
+    #[serde(flatten)]
+    stats: ArticleStats,
+}
+
+impl Frontmatter {
+    /// The keys both styles share; `date` holds the publication date, if any.
+    fn new(article: &PulledArticle, options: &FrontmatterOptions) -> Self {
+        Self {
+            id: options.include_id.then(|| article.platform_id.clone()),
+            title: article.title.clone(),
+            slug: article
+                .url_slug()
+                .unwrap_or_else(|| slugify(&article.title, options.max_slug_len)),
+            date: article.published_at,
+            scheduled_at: None,
+            updated: None,
+            lastmod: None,
+            status: None,
+            draft: None,
+            tags: article.tags.clone(),
+            series: article.series.clone(),
+            canonical_url: article.canonical_url.clone(),
+            cover_image: article.cover_image.clone(),
+            author: article.author.clone(),
+            organization: article.organization.clone(),
+            stats: article.stats,
+        }
+    }
+}
+
+impl FrontmatterWriter for StandardWriter {
+    fn render(&self, article: &PulledArticle, options: &FrontmatterOptions) -> Result<String> {
+        let mut frontmatter = Frontmatter::new(article, options);
+        // Only drafts dated in the future are scheduled; everything else that carries a
+        // date has already been published at that time.
+        let is_scheduled =
+            article.is_draft && article.published_at.is_some_and(|dt| dt > Utc::now());
+        if is_scheduled {
+            frontmatter.scheduled_at = frontmatter.date.take();
+        }
+        frontmatter.updated = article.edited_at;
+        let status = if article.is_draft { "draft" } else { "publish" };
+        frontmatter.status = Some(status.to_string());
+        Ok(serde_yaml::to_string(&frontmatter)?)
+    }
+}
+
+impl FrontmatterWriter for HugoWriter {
+    fn render(&self, article: &PulledArticle, options: &FrontmatterOptions) -> Result<String> {
+        let mut frontmatter = Frontmatter::new(article, options);
+        frontmatter.lastmod = article.edited_at;
+        frontmatter.draft = Some(article.is_draft);
+        Ok(serde_yaml::to_string(&frontmatter)?)
+    }
+}
+
+/// Frontmatter in Forem's own publishing format.
+#[derive(Debug, Serialize)]
+struct ForemFrontmatter {
+    title: String,
+    published: bool,
+    /// Comma-separated, e.g. `rust, cli`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    series: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cover_image: Option<Url>,
+}
+
+impl FrontmatterWriter for ForemWriter {
+    fn render(&self, article: &PulledArticle, _options: &FrontmatterOptions) -> Result<String> {
+        Ok(serde_yaml::to_string(&ForemFrontmatter {
+            title: article.title.clone(),
+            published: !article.is_draft,
+            tags: (!article.tags.is_empty()).then(|| article.tags.join(", ")),
+            series: article.series.clone(),
+            canonical_url: article.canonical_url.clone(),
+            cover_image: article.cover_image.clone(),
+        })?)
+    }
+}
+
+/// Frontmatter of a Jekyll post.
+#[derive(Debug, Serialize)]
+struct JekyllFrontmatter {
+    layout: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    title: String,
+    /// Left out for drafts never published; Jekyll dates them when they're built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+impl FrontmatterWriter for JekyllWriter {
+    fn render(&self, article: &PulledArticle, options: &FrontmatterOptions) -> Result<String> {
+        Ok(serde_yaml::to_string(&JekyllFrontmatter {
+            layout: "post",
+            id: options.include_id.then(|| article.platform_id.clone()),
+            title: article.title.clone(),
+            date: article.published_at,
+            categories: article.series.iter().cloned().collect(),
+            tags: article.tags.clone(),
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+
+    fn draft() -> PulledArticle {
+        PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Draft Post".to_string(),
+            body_markdown: "Content".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec!["rust".to_string()],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: true,
+        }
+    }
+
+    #[test]
+    fn test_each_style_marks_drafts_its_own_way(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = draft();
+
+        assert_eq!(
+            StandardWriter.render(&article, &FrontmatterOptions::default())?,
+            "title: Draft Post\nslug: draft-post\nstatus: draft\ntags:\n- rust\n"
+        );
+        assert_eq!(
+            HugoWriter.render(&article, &FrontmatterOptions::default())?,
+            "title: Draft Post\nslug: draft-post\ndraft: true\ntags:\n- rust\n"
+        );
+        assert_eq!(
+            ForemWriter.render(&article, &FrontmatterOptions::default())?,
+            "title: Draft Post\npublished: false\ntags: rust\n"
+        );
+        assert_eq!(
+            JekyllWriter.render(&article, &FrontmatterOptions::default())?,
+            "layout: post\ntitle: Draft Post\ntags:\n- rust\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_picks_its_writer() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = draft();
+        for (format, writer) in [
+            (
+                FrontmatterFormat::Standard,
+                &StandardWriter as &dyn FrontmatterWriter,
+            ),
+            (FrontmatterFormat::Hugo, &HugoWriter),
+            (FrontmatterFormat::Forem, &ForemWriter),
+            (FrontmatterFormat::Jekyll, &JekyllWriter),
+        ] {
+            assert_eq!(
+                format
+                    .writer()
+                    .render(&article, &FrontmatterOptions::default())?,
+                writer.render(&article, &FrontmatterOptions::default())?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_jekyll_series_is_a_category() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = PulledArticle {
+            title: "Published Post".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            series: Some("Rust CLI Series".to_string()),
+            is_draft: false,
+            ..draft()
+        };
+
+        assert_eq!(
+            JekyllWriter.render(&article, &FrontmatterOptions::default())?,
+            "layout: post\ntitle: Published Post\ndate: 2024-03-15T10:00:00Z\ncategories:\n- Rust CLI Series\ntags:\n- rust\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_id_only_when_requested() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let article = draft();
+        let with_id = FrontmatterOptions {
+            include_id: true,
+            ..FrontmatterOptions::default()
+        };
+
+        for format in [
+            FrontmatterFormat::Standard,
+            FrontmatterFormat::Hugo,
+            FrontmatterFormat::Jekyll,
+        ] {
+            let writer = format.writer();
+            let yaml = writer.render(&article, &FrontmatterOptions::default())?;
+            assert!(!yaml.contains("id:"), "{format:?}");
+            let yaml = writer.render(&article, &with_id)?;
+            assert!(yaml.contains("id: '123'\n"), "{format:?}");
+        }
+
+        // Not a key of Forem's editor, which would reject it
+        let yaml = ForemWriter.render(&article, &with_id)?;
+        assert!(!yaml.contains("id:"));
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Append-only log of pull runs, written as `.puller-history.jsonl`.
+
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::pull::PullReport;
+
+pub const HISTORY_FILENAME: &str = ".puller-history.jsonl";
+
+/// One line of the history, describing a finished run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub platforms: Vec<String>,
+    pub pulled: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    /// Version of puller that ran the pull
+    pub version: String,
+}
+
+impl HistoryEntry {
+    pub fn new(platforms: Vec<String>, report: &PullReport) -> Self {
+        let summary = report.summary();
+        Self {
+            timestamp: Utc::now(),
+            platforms,
+            pulled: summary.pulled,
+            skipped: summary.skipped,
+            duplicates: summary.duplicates,
+            failed: summary.failed,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Appends the entry as one JSON line, creating the file on the first run.
+    pub fn append(&self, output_dir: &Path) -> Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(output_dir.join(HISTORY_FILENAME))?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_history() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let report = PullReport::default();
+
+        HistoryEntry::new(vec!["devto".to_string()], &report).append(dir.path())?;
+        HistoryEntry::new(vec!["forem:vibe".to_string()], &report).append(dir.path())?;
+
+        let content = std::fs::read_to_string(dir.path().join(HISTORY_FILENAME))?;
+        let entries = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<Vec<HistoryEntry>, _>>()?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].platforms, vec!["devto"]);
+        assert_eq!(entries[1].platforms, vec!["forem:vibe"]);
+        assert_eq!(entries[1].pulled, 0);
+        assert_eq!(entries[1].version, env!("CARGO_PKG_VERSION"));
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! Converts raw HTML in article bodies into markdown, for tools that don't render
+//! embedded HTML.
+//!
+//! Converted:
+//!
+//! - HTML blocks (`<table>`, `<figure>`, `<iframe>`, `<div>`, ...) as a whole
+//! - Inline elements within a paragraph (`<a>`, `<img>`, `<strong>`, ...), from the
+//!   opening tag up to its matching closing tag
+//!
+//! Fenced and indented code, code spans and HTML comments are left untouched.
+
+use std::ops::Range;
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Elements without a closing tag.
+const VOID_ELEMENTS: &[&str] = &["area", "br", "col", "embed", "hr", "img", "input", "wbr"];
+
+/// Rewrites the HTML in `body` listed in the module docs, keeping the markdown around it.
+pub fn html_to_markdown(body: &str) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut copied = 0;
+
+    for range in html_ranges(body) {
+        // A block's range takes in its trailing newline, which is kept
+        let html = body[range.clone()].trim_end();
+        output.push_str(&body[copied..range.start]);
+        output.push_str(&fragment_to_markdown(html));
+        copied = range.start + html.len();
+    }
+
+    output.push_str(&body[copied..]);
+    output
+}
+
+/// Converts `html` as a whole into markdown, for text that only comes back
+/// rendered, e.g. comments.
+pub fn fragment_to_markdown(html: &str) -> String {
+    html2md::parse_html(html).trim().to_string()
+}
+
+/// Decodes the entities platforms escape text with, e.g. `&lt;T&gt;` to `<T>`.
+///
+/// `&amp;` goes last, so `&amp;lt;` stays the literal text `&lt;`.
+pub fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Finds the HTML blocks and the outermost inline elements of `body`, in order.
+fn html_ranges(body: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut block: Option<Range<usize>> = None;
+    // Outermost open inline element: its name, where it starts and how deep it nests
+    let mut inline: Option<(String, usize, usize)> = None;
+
+    for (event, range) in Parser::new_ext(body, Options::all()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::HtmlBlock) => block = Some(range.start..range.start),
+            Event::Html(_) => {
+                if let Some(block) = &mut block {
+                    block.end = range.end;
+                }
+            }
+            Event::End(TagEnd::HtmlBlock) => {
+                if let Some(block) = block.take() {
+                    let is_comment = body[block.clone()].trim_start().starts_with("<!--");
+                    if !block.is_empty() && !is_comment {
+                        ranges.push(block);
+                    }
+                }
+            }
+            Event::InlineHtml(html) => {
+                let Some(tag) = parse_tag(&html) else {
+                    continue;
+                };
+                match (tag, inline.take()) {
+                    (HtmlTag::Void, None) => ranges.push(range),
+                    (HtmlTag::Open(name), None) => inline = Some((name, range.start, 1)),
+                    (HtmlTag::Open(name), Some((open, start, depth))) if name == open => {
+                        inline = Some((open, start, depth + 1));
+                    }
+                    (HtmlTag::Close(name), Some((open, start, depth))) if name == open => {
+                        if depth == 1 {
+                            ranges.push(start..range.end);
+                        } else {
+                            inline = Some((open, start, depth - 1));
+                        }
+                    }
+                    (_, open) => inline = open,
+                }
+            }
+            // An element left open at the end of its paragraph stays as it is
+            Event::End(
+                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::TableCell | TagEnd::Item,
+            ) => inline = None,
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+enum HtmlTag {
+    Open(String),
+    Close(String),
+    /// Self-closing or a void element, complete on its own
+    Void,
+}
+
+/// Parses a single inline tag such as `<a href="...">`, `</a>` or `<br/>`.
+fn parse_tag(html: &str) -> Option<HtmlTag> {
+    let inner = html.trim().strip_prefix('<')?.strip_suffix('>')?;
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+
+    let name: String = inner
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        // Comments, processing instructions and declarations
+        return None;
+    }
+
+    Some(if closing {
+        HtmlTag::Close(name)
+    } else if inner.ends_with('/') || VOID_ELEMENTS.contains(&name.as_str()) {
+        HtmlTag::Void
+    } else {
+        HtmlTag::Open(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_table() {
+        let body = "Results:\n\n<table>\n<tr><th>Name</th><th>Score</th></tr>\n<tr><td>Alice</td><td>42</td></tr>\n</table>\n\nDone.\n";
+        let markdown = html_to_markdown(body);
+
+        assert!(markdown.starts_with("Results:\n\n"));
+        assert!(markdown.ends_with("\n\nDone.\n"));
+        assert!(!markdown.contains('<'));
+        assert!(markdown.contains("Name"));
+        assert!(markdown.contains("Alice"));
+        assert!(markdown.contains('|'));
+    }
+
+    #[test]
+    fn test_convert_anchor() {
+        assert_eq!(
+            html_to_markdown("Read <a href=\"https://example.com/post\">the post</a> first.\n"),
+            "Read [the post](https://example.com/post) first.\n"
+        );
+    }
+
+    #[test]
+    fn test_fragment_to_markdown() {
+        let markdown = fragment_to_markdown(
+            "<p>Nice &amp; <a href=\"https://example.com\">useful</a></p>\n\n<p>Use <code>&lt;T&gt;</code></p>",
+        );
+
+        assert!(markdown.starts_with("Nice & [useful](https://example.com)"));
+        assert!(markdown.ends_with("Use `<T>`"));
+        assert!(!markdown.contains("<p>"));
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("&lt;T&gt; &quot;a&quot; &#39;b&#x27;&nbsp;&amp;lt;"),
+            "<T> \"a\" 'b' &lt;"
+        );
+    }
+
+    #[test]
+    fn test_code_is_untouched() {
+        let body = "```html\n<a href=\"https://example.com\">link</a>\n```\n\nInline `<b>bold</b>` too.\n\n    <table></table>\n";
+        assert_eq!(html_to_markdown(body), body);
+    }
+
+    #[test]
+    fn test_comments_and_unclosed_tags_are_untouched() {
+        let body = "<!-- draft note -->\n\nAn <span>unclosed tag.\n";
+        assert_eq!(html_to_markdown(body), body);
+    }
+
+    #[test]
+    fn test_markdown_without_html_is_untouched() {
+        let body = "# Title\n\nSome *text* with [a link](https://example.com).\n";
+        assert_eq!(html_to_markdown(body), body);
+    }
+}
@@ -0,0 +1,231 @@
+//! Finds remote images in article bodies so they can be archived locally.
+
+use std::ops::Range;
+
+use url::Url;
+
+use crate::sanitize::sanitize_component;
+
+/// The target of a markdown image, as written in the body and parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageLink {
+    /// The target exactly as written, which `url` may normalize (trailing slash,
+    /// percent-encoding, ...)
+    pub text: String,
+    pub url: Url,
+}
+
+/// Returns the distinct `http(s)` targets of markdown images (`![alt](url)`) in `body`,
+/// in order of appearance. Images inside fenced code blocks are ignored.
+pub fn find_image_urls(body: &str) -> Vec<ImageLink> {
+    let mut links: Vec<ImageLink> = Vec::new();
+    for range in image_targets(body) {
+        let text = &body[range];
+        if let Ok(url) = Url::parse(text) {
+            if matches!(url.scheme(), "http" | "https")
+                && !links.iter().any(|link| link.text == text)
+            {
+                links.push(ImageLink {
+                    text: text.to_string(),
+                    url,
+                });
+            }
+        }
+    }
+    links
+}
+
+/// Finds where the targets of markdown images (`![alt](target)`) are in `body`, in
+/// order, leaving out images inside fenced code blocks.
+fn image_targets(body: &str) -> Vec<Range<usize>> {
+    let mut targets = Vec::new();
+    let mut in_fence = false;
+    let mut line_start = 0;
+
+    for line in body.split_inclusive('\n') {
+        let offset = line_start;
+        line_start += line.len();
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut pos = 0;
+        while let Some(start) = line[pos..].find("![") {
+            pos += start + 2;
+            let Some(open) = line[pos..].find("](") else {
+                break;
+            };
+            pos += open + 2;
+            // The target ends at `)`, or at the space before an optional title
+            let len = line[pos..].find([')', ' ']).unwrap_or(line.len() - pos);
+            targets.push(offset + pos..offset + pos + len);
+            pos += len;
+        }
+    }
+
+    targets
+}
+
+/// Extension for images whose content type isn't a known image format.
+pub const UNKNOWN_IMAGE_EXTENSION: &str = "bin";
+
+/// Picks a local file name, without extension, for the `index`th image of an article.
+///
+/// The last path segment is kept when there is one, so names stay recognizable;
+/// the index prefix keeps two `image.png` from different hosts apart. The
+/// extension comes from the response instead, see `image_extension`.
+pub fn image_stem(url: &Url, index: usize) -> String {
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map_or_else(|| "image".to_string(), sanitize_component);
+    let name = match name.rsplit_once('.') {
+        Some((stem, extension))
+            if !stem.is_empty()
+                && (1..=5).contains(&extension.len())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            stem.to_string()
+        }
+        _ => name,
+    };
+    format!("{:02}-{name}", index + 1)
+}
+
+/// Returns the file extension for an image's `Content-Type`, ignoring any parameters.
+///
+/// CDNs like Cloudinary serve images from URLs without an extension, so the
+/// header is the only reliable hint. Unknown types give `None`.
+pub fn image_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let extension = match mime.as_str() {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => "jpg",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        "image/svg+xml" => "svg",
+        "image/avif" => "avif",
+        "image/bmp" => "bmp",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "image/tiff" => "tiff",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// Points every markdown image target written exactly as `text` at `local` instead,
+/// with or without a title. Links, and images in fenced code blocks, are left alone,
+/// as `find_image_urls` skips them.
+pub fn replace_image_url(body: &str, text: &str, local: &str) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut copied = 0;
+    for range in image_targets(body) {
+        if body[range.clone()] == *text {
+            output.push_str(&body[copied..range.start]);
+            output.push_str(local);
+            copied = range.end;
+        }
+    }
+    output.push_str(&body[copied..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_image_urls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let body = "Intro ![diagram](https://cdn.example.com/a/diagram.png \"Title\")\n\
+                    ![again](https://cdn.example.com/a/diagram.png) and ![local](./x.png)\n\
+                    ```md\n![skipped](https://cdn.example.com/code.png)\n```\n\
+                    ![last](http://img.example.com/photo.jpg)\n";
+
+        let urls: Vec<_> = find_image_urls(body)
+            .into_iter()
+            .map(|link| link.url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                Url::parse("https://cdn.example.com/a/diagram.png")?,
+                Url::parse("http://img.example.com/photo.jpg")?,
+            ]
+        );
+
+        // The text is kept as written, unlike the parsed URL
+        let links = find_image_urls("![a](https://cdn.example.com)");
+        assert_eq!(links[0].text, "https://cdn.example.com");
+        assert_eq!(links[0].url.as_str(), "https://cdn.example.com/");
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_stem() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let url = Url::parse("https://cdn.example.com/uploads/My%20Photo.png?w=800")?;
+        assert_eq!(image_stem(&url, 0), "01-My%20Photo");
+        assert_eq!(
+            image_stem(&Url::parse("https://cdn.example.com/")?, 11),
+            "12-image"
+        );
+        let cloudinary = Url::parse("https://res.cloudinary.com/demo/image/upload/w_800/abc123")?;
+        assert_eq!(image_stem(&cloudinary, 2), "03-abc123");
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(image_extension("image/png"), Some("png"));
+        assert_eq!(image_extension("image/jpeg"), Some("jpg"));
+        assert_eq!(image_extension("IMAGE/WEBP; charset=binary"), Some("webp"));
+        assert_eq!(image_extension("application/octet-stream"), None);
+        assert_eq!(image_extension(""), None);
+    }
+
+    #[test]
+    fn test_replace_image_url() {
+        let url = "https://cdn.example.com/a.png";
+        assert_eq!(
+            replace_image_url("![a](https://cdn.example.com/a.png \"t\")", url, "01-a.png"),
+            "![a](01-a.png \"t\")"
+        );
+        // A longer URL starting with the same text is left alone
+        assert_eq!(
+            replace_image_url(
+                "![a](https://cdn.example.com/a.png) ![b](https://cdn.example.com/a.png-large)",
+                url,
+                "01-a.png"
+            ),
+            "![a](01-a.png) ![b](https://cdn.example.com/a.png-large)"
+        );
+        // Replaced as written, even when the parsed URL would differ
+        assert_eq!(
+            replace_image_url(
+                "![a](https://cdn.example.com/café.png)",
+                "https://cdn.example.com/café.png",
+                "01-caf%C3%A9.png"
+            ),
+            "![a](01-caf%C3%A9.png)"
+        );
+        // Only image targets outside code fences are rewritten
+        let body = "[see](https://cdn.example.com/a.png) ![a](https://cdn.example.com/a.png)\n\
+                    ```md\n![a](https://cdn.example.com/a.png)\n```\n";
+        assert_eq!(
+            replace_image_url(body, url, "01-a.png"),
+            "[see](https://cdn.example.com/a.png) ![a](01-a.png)\n\
+             ```md\n![a](https://cdn.example.com/a.png)\n```\n"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Rewrites Forem liquid tags in article bodies into plain markdown.
+//!
+//! Handled tags:
+//!
+//! - `{% embed URL %}`, `{% gist URL %}`, `{% codepen URL %}` become `[URL](URL)`
+//! - `{% link URL %}` likewise; `{% link user/slug %}` is resolved on the article's site
+//! - `{% github user/repo %}` becomes `[user/repo](https://github.com/user/repo)`
+//! - `{% youtube ID %}`, `{% twitter ID %}` and `{% tweet ID %}` become links to the video or tweet
+//! - `{% katex %}` ... `{% endkatex %}` becomes a fenced `math` block
+//!
+//! Unknown tags, and anything inside fenced code blocks, are left untouched.
+
+use url::Url;
+
+/// Rewrites the liquid tags listed in the module docs. `site` is the article's URL,
+/// used to resolve relative `{% link %}` paths.
+pub fn convert_embeds(body: &str, site: Option<&Url>) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut in_fence = false;
+    let mut in_katex = false;
+
+    for line in body.split_inclusive('\n') {
+        let (content, newline) = match line.strip_suffix('\n') {
+            Some(content) => (content, "\n"),
+            None => (line, ""),
+        };
+        let trimmed = content.trim();
+
+        if !in_katex && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+        }
+
+        if in_fence {
+            output.push_str(line);
+        } else if !in_katex && parse_tag(trimmed) == Some(("katex", "")) {
+            in_katex = true;
+            output.push_str("```math");
+            output.push_str(newline);
+        } else if in_katex && parse_tag(trimmed) == Some(("endkatex", "")) {
+            in_katex = false;
+            output.push_str("```");
+            output.push_str(newline);
+        } else if in_katex {
+            output.push_str(line);
+        } else {
+            output.push_str(&convert_line(content, site));
+            output.push_str(newline);
+        }
+    }
+
+    output
+}
+
+/// Replaces every convertible `{% ... %}` tag within a single line.
+fn convert_line(line: &str, site: Option<&Url>) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find("{%") {
+        let Some(len) = rest[start..].find("%}") else {
+            break;
+        };
+        let end = start + len + 2;
+        let tag = &rest[start..end];
+
+        output.push_str(&rest[..start]);
+        match parse_tag(tag).and_then(|(name, args)| convert_tag(name, args, site)) {
+            Some(markdown) => output.push_str(&markdown),
+            None => output.push_str(tag),
+        }
+        rest = &rest[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Splits `{% name args %}` into its name and (trimmed) arguments.
+fn parse_tag(tag: &str) -> Option<(&str, &str)> {
+    let inner = tag.strip_prefix("{%")?.strip_suffix("%}")?.trim();
+    let (name, args) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+    Some((name, args.trim()))
+}
+
+fn convert_tag(name: &str, args: &str, site: Option<&Url>) -> Option<String> {
+    // Tags take options after the target, e.g. `{% github user/repo no-readme %}`
+    let target = args.split_whitespace().next()?;
+
+    let url = match name {
+        "embed" | "gist" | "codepen" => Url::parse(target).ok()?,
+        "link" => match Url::parse(target) {
+            Ok(url) => url,
+            // Relative paths are `user/slug` on the article's own Forem
+            Err(_) => site?
+                .join(&format!("/{}", target.trim_start_matches('/')))
+                .ok()?,
+        },
+        "github" => Url::parse(target)
+            .or_else(|_| Url::parse(&format!("https://github.com/{target}")))
+            .ok()?,
+        "youtube" => Url::parse(&format!("https://www.youtube.com/watch?v={target}")).ok()?,
+        "twitter" | "tweet" => {
+            Url::parse(&format!("https://twitter.com/i/status/{target}")).ok()?
+        }
+        _ => return None,
+    };
+
+    let text = match name {
+        "github" if !target.contains("://") => target.to_string(),
+        "youtube" => "YouTube video".to_string(),
+        "twitter" | "tweet" => "Tweet".to_string(),
+        _ => url.to_string(),
+    };
+
+    Some(format!("[{text}]({url})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_embed() {
+        assert_eq!(
+            convert_embeds("See {% embed https://example.com/post %} here\n", None),
+            "See [https://example.com/post](https://example.com/post) here\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_link() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let site = Url::parse("https://dev.to/alice/my-post-1a2b")?;
+
+        assert_eq!(
+            convert_embeds("{% link https://dev.to/bob/other %}", Some(&site)),
+            "[https://dev.to/bob/other](https://dev.to/bob/other)"
+        );
+        assert_eq!(
+            convert_embeds("{% link bob/other %}", Some(&site)),
+            "[https://dev.to/bob/other](https://dev.to/bob/other)"
+        );
+        // A relative link cannot be resolved without the article's URL
+        assert_eq!(
+            convert_embeds("{% link bob/other %}", None),
+            "{% link bob/other %}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_github() {
+        assert_eq!(
+            convert_embeds("{% github rust-lang/rust no-readme %}", None),
+            "[rust-lang/rust](https://github.com/rust-lang/rust)"
+        );
+        assert_eq!(
+            convert_embeds("{% github https://github.com/rust-lang/rust/issues/1 %}", None),
+            "[https://github.com/rust-lang/rust/issues/1](https://github.com/rust-lang/rust/issues/1)"
+        );
+    }
+
+    #[test]
+    fn test_convert_youtube_and_tweet() {
+        assert_eq!(
+            convert_embeds("{% youtube dQw4w9WgXcQ %}", None),
+            "[YouTube video](https://www.youtube.com/watch?v=dQw4w9WgXcQ)"
+        );
+        assert_eq!(
+            convert_embeds("{% twitter 1234567890 %}", None),
+            "[Tweet](https://twitter.com/i/status/1234567890)"
+        );
+    }
+
+    #[test]
+    fn test_convert_katex_block() {
+        assert_eq!(
+            convert_embeds("{% katex %}\nx^2 + y^2\n{% endkatex %}\n", None),
+            "```math\nx^2 + y^2\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_tags_and_code_blocks_untouched() {
+        let body = "{% details Click me %}\nHidden\n{% enddetails %}\n\
+                    ```liquid\n{% embed https://example.com %}\n```\n";
+        assert_eq!(convert_embeds(body, None), body);
+    }
+}
@@ -0,0 +1,145 @@
+//! Summary index of every archived article, written as `articles.json`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::article::PulledArticle;
+use crate::error::Result;
+
+pub const MANIFEST_FILENAME: &str = "articles.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub platform_id: String,
+    pub platform: String,
+    pub title: String,
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub local_path: String,
+}
+
+/// Manifest entries keyed by platform id, so re-pulls replace rather than duplicate.
+#[derive(Debug, Default)]
+pub struct Manifest {
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Loads the existing manifest so articles from earlier runs stay listed.
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        let path = output_dir.join(MANIFEST_FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let entries: Vec<ManifestEntry> = serde_json::from_str(&content)?;
+        Ok(Self {
+            entries: entries
+                .into_iter()
+                .map(|e| (e.platform_id.clone(), e))
+                .collect(),
+        })
+    }
+
+    pub fn record(&mut self, article: &PulledArticle, local_path: &str) {
+        let platform_id = format!("{}:{}", article.platform, article.platform_id);
+        self.entries.insert(
+            platform_id.clone(),
+            ManifestEntry {
+                platform_id,
+                platform: article.platform.to_string(),
+                title: article.title.clone(),
+                published_at: article.published_at,
+                tags: article.tags.clone(),
+                local_path: local_path.to_string(),
+            },
+        );
+    }
+
+    pub fn article_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns entries newest first, with undated drafts at the end.
+    pub fn entries(&self) -> Vec<&ManifestEntry> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        entries
+    }
+
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.entries())?;
+        std::fs::write(output_dir.join(MANIFEST_FILENAME), content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::article::ArticleStats;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+    use tempfile::TempDir;
+
+    fn article(id: &str, published_at: Option<&str>) -> PulledArticle {
+        PulledArticle {
+            platform_id: id.to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: format!("Article {id}"),
+            body_markdown: "Body".to_string(),
+            published_at: published_at.and_then(|d| d.parse().ok()),
+            edited_at: None,
+            url: None,
+            tags: vec!["rust".to_string()],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: published_at.is_none(),
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip_merges_runs() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = TempDir::new()?;
+
+        let mut first_run = Manifest::load(dir.path())?;
+        first_run.record(&article("1", Some("2024-01-01T10:00:00Z")), "devto/a.md");
+        first_run.save(dir.path())?;
+
+        let mut second_run = Manifest::load(dir.path())?;
+        second_run.record(&article("2", Some("2024-02-01T10:00:00Z")), "devto/b.md");
+        second_run.record(&article("3", None), "devto/draft.md");
+        second_run.save(dir.path())?;
+
+        let loaded = Manifest::load(dir.path())?;
+        let ids: Vec<_> = loaded
+            .entries()
+            .iter()
+            .map(|e| e.platform_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["devto:2", "devto:1", "devto:3"]);
+        assert_eq!(loaded.entries()[0].tags, vec!["rust".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_record_replaces_existing() {
+        let mut manifest = Manifest::default();
+        manifest.record(&article("1", None), "devto/draft-article-1.md");
+        manifest.record(&article("1", Some("2024-01-01T10:00:00Z")), "devto/a.md");
+
+        assert_eq!(manifest.article_count(), 1);
+        assert_eq!(manifest.entries()[0].local_path, "devto/a.md");
+    }
+}
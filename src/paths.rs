@@ -0,0 +1,159 @@
+//! Expands `~` and environment variables in path arguments.
+//!
+//! Shells expand them before puller sees its arguments, but not inside quotes,
+//! after `--flag=`, or in values read from the environment or a `.env` file:
+//!
+//! - A leading `~` or `~/` becomes the home directory (`HOME`, or `USERPROFILE`
+//!   on Windows); `~user` is left alone
+//! - `$VAR` and `${VAR}` become the variable's value; a `$` not followed by a
+//!   variable name is kept as is
+//! - `$$` is a literal `$`, e.g. `$$RECYCLE.BIN`
+//!
+//! An unset variable is an error rather than an empty string, which could turn
+//! `$ARCHIVE/posts` into `/posts`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::error::{PullError, Result};
+
+/// Expands `path` as described in the module docs, reading the environment.
+///
+/// Paths that aren't valid UTF-8 are returned unchanged.
+pub fn expand_path(path: &Path) -> Result<PathBuf> {
+    match path.to_str() {
+        Some(raw) => expand_with(raw, |name| env::var(name).ok()).map(PathBuf::from),
+        None => Ok(path.to_path_buf()),
+    }
+}
+
+/// Expands `raw`, looking variables up with `var`.
+fn expand_with(raw: &str, var: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(raw.len());
+
+    let rest = match raw.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let home = var("HOME").or_else(|| var("USERPROFILE")).ok_or_else(|| {
+                PullError::InvalidConfig(format!(
+                    "Cannot expand ~ in {raw}: the home directory is unknown (HOME is not set)"
+                ))
+            })?;
+            expanded.push_str(&home);
+            rest
+        }
+        _ => raw,
+    };
+
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let after = &rest[index + 1..];
+        if after.starts_with('$') {
+            expanded.push('$');
+            chars.next();
+            continue;
+        }
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) if is_var_name(&braced[..end]) => (&braced[..end], end + 2),
+                _ => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                let name = &after[..end];
+                if is_var_name(name) {
+                    (name, end)
+                } else {
+                    ("", 0)
+                }
+            }
+        };
+        if name.is_empty() {
+            expanded.push('$');
+            continue;
+        }
+
+        let value = var(name).ok_or_else(|| {
+            PullError::InvalidConfig(format!(
+                "Cannot expand ${name} in {raw}: the variable is not set"
+            ))
+        })?;
+        expanded.push_str(&value);
+        // Skip the rest of the name (and braces); all of it is ASCII
+        for _ in 0..len {
+            chars.next();
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Whether `name` can be a variable name: letters, digits and `_`, not starting
+/// with a digit.
+fn is_var_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(raw: &str) -> Result<String> {
+        expand_with(raw, |name| match name {
+            "HOME" => Some("/home/me".to_string()),
+            "ARCHIVE" => Some("/srv/archive".to_string()),
+            "SITE_1" => Some("blog".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_expand_home() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(expand("~")?, "/home/me");
+        assert_eq!(expand("~/secrets/devto")?, "/home/me/secrets/devto");
+        // Only a leading ~ of the current user
+        assert_eq!(expand("~alice/posts")?, "~alice/posts");
+        assert_eq!(expand("posts/~")?, "posts/~");
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_variables() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(expand("$ARCHIVE/posts")?, "/srv/archive/posts");
+        assert_eq!(expand("${ARCHIVE}_old/$SITE_1")?, "/srv/archive_old/blog");
+        assert_eq!(expand("~/$SITE_1.md")?, "/home/me/blog.md");
+        Ok(())
+    }
+
+    #[test]
+    fn test_dollar_without_name_is_kept() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(expand("price$")?, "price$");
+        assert_eq!(expand("$1/a$-b")?, "$1/a$-b");
+        assert_eq!(expand("${unclosed")?, "${unclosed");
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_dollar_is_literal() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(expand("D:/$$RECYCLE.BIN")?, "D:/$RECYCLE.BIN");
+        assert_eq!(expand("$$$ARCHIVE")?, "$/srv/archive");
+        assert_eq!(expand("a$$$$b")?, "a$$b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unset_variable_is_an_error() {
+        assert!(matches!(
+            expand("$MISSING/posts"),
+            Err(PullError::InvalidConfig(message)) if message.contains("$MISSING")
+        ));
+        assert!(expand_with("~/posts", |_| None).is_err());
+    }
+}
@@ -2,27 +2,65 @@ use std::fmt;
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 use crate::error::PullError;
 use crate::forem::ForemInstance;
+use crate::writefreely::WriteFreelyInstance;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Platform {
+    /// Any RSS or Atom feed, identified by its URL
+    Rss(Url),
+    /// A Ghost site, identified by its URL
+    Ghost(Url),
+    /// Articles imported from a Medium export archive, which has no API to pull from
+    Medium,
+    /// A Bluesky account, identified by its handle (e.g. `alice.bsky.social`)
+    Bluesky(String),
+    /// A WriteFreely blog (collection) on write.as or a self-hosted instance
+    WriteFreely {
+        instance: WriteFreelyInstance,
+        collection: String,
+    },
     /// Forem-based platforms (dev.to, vibe.forem.com, etc.)
+    ///
+    /// Untagged so a bare instance name (`"devto"`) stays valid; serde requires
+    /// untagged variants to come last.
     #[serde(untagged)]
     Forem(ForemInstance),
 }
 
 impl Platform {
-    /// Returns the ForemInstance for this platform.
+    /// Returns the ForemInstance for this platform, if it is a Forem.
     #[must_use]
-    pub fn as_forem(&self) -> &ForemInstance {
+    pub fn as_forem(&self) -> Option<&ForemInstance> {
         match self {
-            Platform::Forem(instance) => instance,
+            Platform::Forem(instance) => Some(instance),
+            Platform::Rss(_)
+            | Platform::Ghost(_)
+            | Platform::Medium
+            | Platform::Bluesky(_)
+            | Platform::WriteFreely { .. } => None,
         }
     }
 
+    /// Returns every built-in platform (custom Forem instances excluded).
+    #[must_use]
+    pub fn all_builtin() -> Vec<Self> {
+        ForemInstance::all()
+            .into_iter()
+            .map(Platform::Forem)
+            .collect()
+    }
+
+    /// Detects the platform an article URL belongs to.
+    #[must_use]
+    pub fn from_url(url: &Url) -> Option<Self> {
+        ForemInstance::from_url(url).map(Platform::Forem)
+    }
+
     #[must_use]
     pub fn as_str(&self) -> String {
         match self {
@@ -34,6 +72,14 @@ impl Platform {
                     format!("forem:{}", instance)
                 }
             }
+            Self::Rss(feed_url) => format!("rss:{feed_url}"),
+            Self::Ghost(site_url) => format!("ghost:{site_url}"),
+            Self::Medium => "medium".to_string(),
+            Self::Bluesky(handle) => format!("bluesky:{handle}"),
+            Self::WriteFreely {
+                instance,
+                collection,
+            } => format!("writefreely:{instance}/{collection}"),
         }
     }
 }
@@ -48,13 +94,49 @@ impl FromStr for Platform {
     type Err = PullError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Feed and site URLs are case-sensitive, so they are split off before lowercasing
+        if let Some(feed_url) = strip_prefix_ignore_case(s, "rss:") {
+            return Ok(Platform::Rss(parse_platform_url(feed_url)?));
+        }
+        if let Some(site_url) = strip_prefix_ignore_case(s, "ghost:") {
+            return Ok(Platform::Ghost(parse_platform_url(site_url)?));
+        }
+
         let lower = s.to_lowercase();
 
+        if let Some(handle) = lower.strip_prefix("bluesky:") {
+            // Handles are case-insensitive, and often written with a leading @
+            let handle = handle.trim_start_matches('@');
+            if handle.is_empty() {
+                return Err(PullError::UnsupportedPlatform(
+                    "Bluesky needs a handle, e.g. bluesky:alice.bsky.social".to_string(),
+                ));
+            }
+            return Ok(Platform::Bluesky(handle.to_string()));
+        }
+
+        // e.g. "writefreely:writeas/alice" or "writefreely:custom:blog.example.com/alice"
+        if let Some(blog) = lower.strip_prefix("writefreely:") {
+            let Some((instance, collection)) = blog
+                .rsplit_once('/')
+                .filter(|(_, collection)| !collection.is_empty())
+            else {
+                return Err(PullError::UnsupportedPlatform(format!(
+                    "WriteFreely needs an instance and a blog alias, e.g. writefreely:writeas/alice (got {s})"
+                )));
+            };
+            return Ok(Platform::WriteFreely {
+                instance: instance.parse()?,
+                collection: collection.to_string(),
+            });
+        }
+
         // Backward compatibility: "devto", "dev.to", "dev" map directly to Forem(DevTo)
         // Also "vibeforem", "vibe.forem", "vibe" map to Forem(Vibe)
         match lower.as_str() {
             "devto" | "dev.to" | "dev" => Ok(Platform::Forem(ForemInstance::DevTo)),
             "vibeforem" | "vibe.forem" | "vibe" => Ok(Platform::Forem(ForemInstance::Vibe)),
+            "medium" => Ok(Platform::Medium),
             _ => {
                 // Check for forem: prefix (e.g., "forem:vibe", "forem:custom:example.com")
                 if let Some(instance_str) = lower.strip_prefix("forem:") {
@@ -70,6 +152,17 @@ impl FromStr for Platform {
     }
 }
 
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+fn parse_platform_url(url: &str) -> Result<Url, PullError> {
+    Url::parse(url)
+        .map_err(|e| PullError::UnsupportedPlatform(format!("Invalid platform URL {url}: {e}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,9 +289,111 @@ mod tests {
         assert_eq!(p, p_clone);
     }
 
+    #[test]
+    fn test_all_builtin_parse_back() {
+        for platform in Platform::all_builtin() {
+            assert_eq!(platform.to_string().parse::<Platform>().unwrap(), platform);
+        }
+    }
+
+    #[test]
+    fn test_from_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let url = Url::parse("https://dev.to/user/my-post-abc")?;
+        assert_eq!(
+            Platform::from_url(&url),
+            Some(Platform::Forem(ForemInstance::DevTo))
+        );
+
+        let url = Url::parse("https://vibe.forem.com/user/my-post")?;
+        assert_eq!(
+            Platform::from_url(&url),
+            Some(Platform::Forem(ForemInstance::Vibe))
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_as_forem() {
         let devto = Platform::Forem(ForemInstance::DevTo);
-        assert_eq!(devto.as_forem(), &ForemInstance::DevTo);
+        assert_eq!(devto.as_forem(), Some(&ForemInstance::DevTo));
+    }
+
+    #[test]
+    fn test_rss_round_trip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform: Platform = "RSS:https://blog.example.com/Feed.xml".parse()?;
+        assert_eq!(
+            platform,
+            Platform::Rss("https://blog.example.com/Feed.xml".parse()?)
+        );
+        assert_eq!(
+            platform.to_string(),
+            "rss:https://blog.example.com/Feed.xml"
+        );
+        assert_eq!(platform.to_string().parse::<Platform>()?, platform);
+        assert_eq!(platform.as_forem(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rss_invalid_url() {
+        assert!(matches!(
+            "rss:not a url".parse::<Platform>(),
+            Err(PullError::UnsupportedPlatform(_))
+        ));
+    }
+
+    #[test]
+    fn test_ghost_round_trip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform: Platform = "ghost:https://blog.example.com/".parse()?;
+        assert_eq!(
+            platform,
+            Platform::Ghost("https://blog.example.com/".parse()?)
+        );
+        assert_eq!(platform.to_string(), "ghost:https://blog.example.com/");
+        assert_eq!(platform.to_string().parse::<Platform>()?, platform);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bluesky_round_trip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform: Platform = "Bluesky:@Alice.bsky.social".parse()?;
+        assert_eq!(platform, Platform::Bluesky("alice.bsky.social".to_string()));
+        assert_eq!(platform.to_string(), "bluesky:alice.bsky.social");
+        assert_eq!(platform.to_string().parse::<Platform>()?, platform);
+        assert!("bluesky:".parse::<Platform>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_writefreely_round_trip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform: Platform = "writefreely:write.as/Alice".parse()?;
+        assert_eq!(
+            platform,
+            Platform::WriteFreely {
+                instance: WriteFreelyInstance::WriteAs,
+                collection: "alice".to_string(),
+            }
+        );
+        assert_eq!(platform.to_string(), "writefreely:writeas/alice");
+
+        let custom: Platform = "writefreely:custom:blog.example.com/notes".parse()?;
+        assert_eq!(
+            custom.to_string(),
+            "writefreely:custom:blog.example.com/notes"
+        );
+        assert_eq!(custom.to_string().parse::<Platform>()?, custom);
+        assert!("writefreely:writeas".parse::<Platform>().is_err());
+        assert!("writefreely:writeas/".parse::<Platform>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_medium_round_trip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let platform: Platform = "Medium".parse()?;
+        assert_eq!(platform, Platform::Medium);
+        assert_eq!(platform.to_string(), "medium");
+        assert_eq!(serde_json::to_string(&platform)?, "\"medium\"");
+        assert_eq!(platform.as_forem(), None);
+        Ok(())
     }
 }
@@ -0,0 +1,913 @@
+//! The fetch-and-write loop behind `puller pull`.
+
+use std::collections::BTreeSet;
+use std::fmt::Arguments;
+use std::io::Write;
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::Serialize;
+use tracing::{error, info_span, warn, Instrument};
+use url::Url;
+
+use crate::adapters::Puller;
+use crate::article::{normalize_url, ArticleStats, PulledArticle};
+use crate::error::{PullError, Result};
+use crate::html::html_to_markdown;
+use crate::liquid::convert_embeds;
+use crate::manifest::Manifest;
+use crate::state::{PullState, SharedPullState};
+use crate::writer::{WriteStatus, Writer};
+
+/// How a queued article is fetched.
+pub enum Target {
+    Id(String),
+    Url(Url),
+}
+
+/// An article waiting to be fetched, with the label used in progress output.
+pub struct QueuedArticle {
+    pub target: Target,
+    pub label: String,
+}
+
+/// An article that was fetched and written, or found unchanged.
+#[derive(Debug)]
+pub struct PulledFile {
+    pub label: String,
+    /// Path relative to the output directory
+    pub path: String,
+    /// Never `WriteStatus::Skipped`, those articles end up in `PullReport::skipped`.
+    /// `Unchanged` includes articles the platform reported as not modified.
+    pub status: WriteStatus,
+}
+
+/// Why an article was left out of a pull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Already in the state (or on disk) and not edited since
+    AlreadyPulled,
+    /// The local file differs and was kept, see `--overwrite-policy`
+    LocalChanges,
+    /// A cross-post of an article that was already pulled (`--dedupe-by-canonical`)
+    Duplicate,
+    /// The body is longer than `PullContext::max_body_bytes`
+    Oversized,
+}
+
+#[derive(Debug)]
+pub struct SkippedArticle {
+    pub label: String,
+    /// The existing file, or the original's file for a duplicate; empty for an
+    /// oversized article
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+/// Everything that happened during a pull, for the CLI to print or a caller to inspect.
+#[derive(Debug, Default)]
+pub struct PullReport {
+    pub pulled: Vec<PulledFile>,
+    pub skipped: Vec<SkippedArticle>,
+    /// Articles that failed in continue-on-error mode, by label
+    pub failed: Vec<(String, PullError)>,
+    /// Series of every pulled article, used to write series indexes
+    pub series: BTreeSet<String>,
+    /// Article list pages skipped with `PullOptions::skip_failed_pages`
+    pub skipped_pages: Vec<u32>,
+    /// Articles written with a truncated body, see `PullContext::truncate_oversized`
+    pub truncated: usize,
+}
+
+/// The counts of a `PullReport`, with the paths of the files written.
+#[derive(Debug, Serialize)]
+pub struct ReportSummary<'a> {
+    pub pulled: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+    /// Skipped articles whose body exceeded `--max-body-bytes`, not included in `skipped`
+    pub oversized: usize,
+    /// Articles written with their body cut at `--max-body-bytes`
+    pub truncated: usize,
+    pub failed: usize,
+    /// Article list pages that failed and were skipped, so the pull may be incomplete
+    pub skipped_pages: &'a [u32],
+    /// Files created or overwritten, relative to the output directory
+    pub written: Vec<&'a str>,
+}
+
+impl PullReport {
+    /// Files created, or that would be in dry-run mode.
+    pub fn created(&self) -> usize {
+        self.count_pulled(|status| matches!(status, WriteStatus::Created | WriteStatus::WouldWrite))
+    }
+
+    /// Files overwritten, or that would be in dry-run mode.
+    pub fn updated(&self) -> usize {
+        self.count_pulled(|status| {
+            matches!(status, WriteStatus::Updated | WriteStatus::WouldUpdate)
+        })
+    }
+
+    pub fn unchanged(&self) -> usize {
+        self.count_pulled(|status| status == WriteStatus::Unchanged)
+    }
+
+    pub fn skipped_for(&self, reason: SkipReason) -> usize {
+        self.skipped.iter().filter(|s| s.reason == reason).count()
+    }
+
+    /// Skipped articles other than duplicates and oversized ones, which are counted
+    /// on their own.
+    pub fn skipped_other(&self) -> usize {
+        self.skipped.len()
+            - self.skipped_for(SkipReason::Duplicate)
+            - self.skipped_for(SkipReason::Oversized)
+    }
+
+    /// The totals line of the text summary.
+    pub fn totals(&self) -> String {
+        format!(
+            "Pulled: {} (new: {}, updated: {}, unchanged: {}), Skipped: {}",
+            self.pulled.len(),
+            self.created(),
+            self.updated(),
+            self.unchanged(),
+            self.skipped_other()
+        )
+    }
+
+    /// Condenses the report for `puller pull --output json`.
+    pub fn summary(&self) -> ReportSummary<'_> {
+        ReportSummary {
+            pulled: self.pulled.len(),
+            skipped: self.skipped_other(),
+            duplicates: self.skipped_for(SkipReason::Duplicate),
+            oversized: self.skipped_for(SkipReason::Oversized),
+            truncated: self.truncated,
+            failed: self.failed.len(),
+            skipped_pages: &self.skipped_pages,
+            written: self
+                .pulled
+                .iter()
+                .filter(|p| matches!(p.status, WriteStatus::Created | WriteStatus::Updated))
+                .map(|p| p.path.as_str())
+                .collect(),
+        }
+    }
+
+    fn count_pulled(&self, include: impl Fn(WriteStatus) -> bool) -> usize {
+        self.pulled.iter().filter(|p| include(p.status)).count()
+    }
+}
+
+/// How `--print` writes rendered articles to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintMode {
+    Off,
+    /// A single article exactly as the file reads, for piping
+    Single,
+    /// Every article after a `==> {path} <==` header line
+    WithHeaders,
+}
+
+pub struct PullContext<'a> {
+    pub puller: &'a dyn Puller,
+    pub writer: &'a Writer<'a>,
+    /// Re-download even when the stored `ETag` still matches
+    pub force: bool,
+    /// Record failed articles and keep going instead of aborting
+    pub continue_on_error: bool,
+    /// Rewrite Forem liquid tags in article bodies as plain markdown
+    pub convert_embeds: bool,
+    /// Convert raw HTML in article bodies to markdown
+    pub html_to_markdown: bool,
+    /// Keep reading time and reaction counts in the frontmatter
+    pub include_stats: bool,
+    /// Also archive each article's comments next to it
+    pub include_comments: bool,
+    /// Skip articles whose canonical URL matches an already pulled article
+    pub dedupe_by_canonical: bool,
+    /// Use an article's own URL as its canonical URL when the platform has none
+    pub canonical_from_url: bool,
+    /// Skip articles whose markdown body is longer than this many bytes
+    pub max_body_bytes: Option<usize>,
+    /// Write oversized articles with a truncated body instead of skipping them
+    pub truncate_oversized: bool,
+    /// Whether rendered articles are also written to stdout
+    pub print: PrintMode,
+    /// Progress bar replacing the per-article log lines, see `progress_bar`
+    pub progress: Option<ProgressBar>,
+    /// Write the log to stderr, keeping stdout for machine-readable output
+    pub log_to_stderr: bool,
+    /// Leave out the log altogether; errors and the final summary are printed elsewhere
+    pub quiet: bool,
+}
+
+/// Creates a progress bar on stdout for `len` queued articles.
+pub fn progress_bar(len: usize) -> ProgressBar {
+    let bar = ProgressBar::with_draw_target(Some(len as u64), ProgressDrawTarget::stdout());
+    bar.set_style(
+        ProgressStyle::with_template("  [{bar:30}] {pos}/{len} {wide_msg}")
+            .expect("progress template is valid")
+            .progress_chars("=> "),
+    );
+    bar
+}
+
+impl PullContext<'_> {
+    /// Fetches and writes every queued article, updating `state` and `manifest`.
+    pub async fn pull_queue(
+        &self,
+        queue: &[QueuedArticle],
+        state: &SharedPullState,
+        manifest: &mut Manifest,
+    ) -> Result<PullReport> {
+        let mut report = PullReport::default();
+        for item in queue {
+            self.pull_item(item, state, manifest, &mut report).await?;
+        }
+        self.finish();
+        Ok(report)
+    }
+
+    /// Fetches and writes one article, recording what happened in `report`.
+    ///
+    /// Failures are recorded too in continue-on-error mode; otherwise the progress
+    /// bar is abandoned and the error returned.
+    pub async fn pull_item(
+        &self,
+        item: &QueuedArticle,
+        state: &SharedPullState,
+        manifest: &mut Manifest,
+        report: &mut PullReport,
+    ) -> Result<()> {
+        match &self.progress {
+            Some(bar) => bar.set_message(item.label.clone()),
+            None => self.write_line(format_args!("  Pulling: {}", item.label)),
+        }
+
+        let result = self
+            .pull_one(item, state, manifest, report)
+            .instrument(info_span!("article", label = %item.label))
+            .await;
+        if let Some(bar) = &self.progress {
+            bar.inc(1);
+        }
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) if self.continue_on_error => {
+                match &self.progress {
+                    Some(bar) => bar.suspend(|| error!(article = %item.label, "{e}")),
+                    None => error!(article = %item.label, "{e}"),
+                }
+                report.failed.push((item.label.clone(), e));
+                Ok(())
+            }
+            Err(e) => {
+                if let Some(bar) = &self.progress {
+                    bar.abandon();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Clears the progress bar once every article is pulled.
+    pub fn finish(&self) {
+        if let Some(bar) = &self.progress {
+            bar.finish_and_clear();
+        }
+    }
+
+    /// Prints a line even when the progress bar is shown, above the bar.
+    pub fn println(&self, line: Arguments) {
+        match &self.progress {
+            Some(bar) if !bar.is_finished() => bar.println(line.to_string()),
+            _ => self.write_line(line),
+        }
+    }
+
+    /// Prints a per-article detail line unless the progress bar is shown.
+    fn log(&self, line: Arguments) {
+        if self.progress.is_none() {
+            self.write_line(line);
+        }
+    }
+
+    fn write_line(&self, line: Arguments) {
+        if self.quiet {
+            return;
+        }
+        if self.log_to_stderr {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// Writes a rendered article to `out` as `self.print` says; stdout outside of tests.
+    fn print_article(&self, out: &mut impl Write, path: &str, content: &str) -> Result<()> {
+        match self.print {
+            PrintMode::Off => {}
+            PrintMode::Single => write!(out, "{content}")?,
+            PrintMode::WithHeaders => writeln!(out, "==> {path} <==\n{content}")?,
+        }
+        Ok(())
+    }
+
+    /// Returns the path of an already pulled article that `article` is a cross-post of.
+    ///
+    /// The canonical source itself (its own URL is the canonical one) is never
+    /// treated as a duplicate, so it is kept even when a cross-post came first.
+    fn find_original(&self, article: &PulledArticle, state: &PullState) -> Option<String> {
+        let canonical = article.canonical_key()?;
+        if article
+            .url
+            .as_ref()
+            .is_some_and(|url| normalize_url(url) == canonical)
+        {
+            return None;
+        }
+
+        let platform_id = format!("{}:{}", article.platform, article.platform_id);
+        state
+            .find_canonical(&canonical, &platform_id)
+            .map(|(_, entry)| entry.local_path.clone())
+    }
+
+    async fn pull_one(
+        &self,
+        item: &QueuedArticle,
+        state: &SharedPullState,
+        manifest: &mut Manifest,
+        report: &mut PullReport,
+    ) -> Result<()> {
+        let label = item.label.clone();
+        let mut article = match &item.target {
+            Target::Id(id) => {
+                // Revalidate with the stored ETag; --force always re-downloads, and
+                // --print needs the article even when it is unchanged
+                let platform_id = format!("{}:{id}", self.puller.platform());
+                let etag = if self.force || self.print != PrintMode::Off {
+                    None
+                } else {
+                    state.lock().get_etag(&platform_id).map(str::to_owned)
+                };
+
+                match self
+                    .puller
+                    .fetch_article_if_modified(id, etag.as_deref())
+                    .await?
+                {
+                    Some(article) => article,
+                    None => {
+                        self.log(format_args!(
+                            "    Not modified on {}",
+                            self.puller.platform()
+                        ));
+                        report.pulled.push(PulledFile {
+                            label,
+                            path: state
+                                .lock()
+                                .get_local_path(&platform_id)
+                                .unwrap_or_default()
+                                .to_string(),
+                            status: WriteStatus::Unchanged,
+                        });
+                        return Ok(());
+                    }
+                }
+            }
+            Target::Url(url) => self.puller.fetch_article_by_url(url).await?,
+        };
+
+        if self.dedupe_by_canonical {
+            let original = self.find_original(&article, &state.lock());
+            if let Some(original) = original {
+                self.log(format_args!("    Duplicate of: {original}"));
+                report.skipped.push(SkippedArticle {
+                    label,
+                    path: original,
+                    reason: SkipReason::Duplicate,
+                });
+                return Ok(());
+            }
+        }
+
+        if !self.include_stats {
+            article.stats = ArticleStats::default();
+        }
+        if self.convert_embeds {
+            article.body_markdown = convert_embeds(&article.body_markdown, article.url.as_ref());
+        }
+        if self.html_to_markdown {
+            article.body_markdown = html_to_markdown(&article.body_markdown);
+        }
+        if self.canonical_from_url && article.canonical_url.is_none() {
+            article.canonical_url.clone_from(&article.url);
+        }
+
+        if let Some(max) = self.max_body_bytes {
+            let len = article.body_markdown.len();
+            if len > max {
+                if !self.truncate_oversized {
+                    // A warning on stderr, unlike the per-article lines on stdout
+                    let oversized = || warn!(article = %label, len, max, "skipping article over the size limit");
+                    match &self.progress {
+                        Some(bar) => bar.suspend(oversized),
+                        None => oversized(),
+                    }
+                    report.skipped.push(SkippedArticle {
+                        label,
+                        path: String::new(),
+                        reason: SkipReason::Oversized,
+                    });
+                    return Ok(());
+                }
+                truncate_body(&mut article.body_markdown, max);
+                self.log(format_args!("    Truncated: body is {len} bytes"));
+                report.truncated += 1;
+            }
+        }
+
+        let images = self.writer.download_images(&mut article).await?;
+        if images > 0 {
+            self.log(format_args!("    Images: {images} downloaded"));
+        }
+
+        let outcome = self.writer.write_article(&article, &mut state.lock())?;
+        let path = &outcome.path;
+
+        match outcome.status {
+            WriteStatus::Created => self.log(format_args!("    Wrote: {path}")),
+            WriteStatus::Updated => self.log(format_args!("    Updated: {path}")),
+            WriteStatus::Unchanged => self.log(format_args!("    Unchanged: {path}")),
+            WriteStatus::WouldWrite => self.log(format_args!("    Would write: {path}")),
+            WriteStatus::WouldUpdate => {
+                self.log(format_args!("    Would update: {path}"));
+                if let Some(diff) = &outcome.diff {
+                    self.log(format_args!("{diff}"));
+                }
+            }
+            WriteStatus::Skipped => self.log(format_args!(
+                "    Skipped: {path} (differs locally, not overwritten)"
+            )),
+        }
+        self.print_article(&mut std::io::stdout().lock(), path, &outcome.content)?;
+
+        if outcome.status == WriteStatus::Skipped {
+            report.skipped.push(SkippedArticle {
+                label,
+                path: path.clone(),
+                reason: SkipReason::LocalChanges,
+            });
+        } else {
+            report.pulled.push(PulledFile {
+                label,
+                path: path.clone(),
+                status: outcome.status,
+            });
+        }
+
+        if self.include_comments {
+            let comments = self.puller.fetch_comments(&article.platform_id).await?;
+            if !comments.is_empty() {
+                let comments_path = self.writer.write_comments(path, &article, &comments)?;
+                self.log(format_args!(
+                    "    Comments: {comments_path} ({} threads)",
+                    comments.len()
+                ));
+            }
+        }
+
+        if let Some(series) = &article.series {
+            report.series.insert(series.clone());
+        }
+        manifest.record(&article, &outcome.path);
+
+        Ok(())
+    }
+}
+
+/// Cuts `body` to at most `max` bytes on a character boundary and appends a note
+/// saying how long the original was.
+fn truncate_body(body: &mut String, max: usize) {
+    let len = body.len();
+    let mut end = max;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    body.truncate(end);
+    body.push_str(&format!(
+        "\n\n*[Truncated: the original body is {len} bytes, over the {max}-byte limit]*\n"
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::forem::{ForemPuller, DEFAULT_API_VERSION};
+    use crate::adapters::HttpOptions;
+    use crate::forem::ForemInstance;
+    use crate::frontmatter::{FrontmatterOptions, FrontmatterWriter, StandardWriter};
+    use crate::writer::FolderStructure;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mount_article(server: &MockServer, id: u64) {
+        Mock::given(method("GET"))
+            .and(path(format!("/articles/{id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": id,
+                "title": format!("Article {id}"),
+                "body_markdown": "Body",
+                "published_at": "2024-03-15T10:00:00Z",
+                "url": format!("https://dev.to/user/article-{id}"),
+                "tags": [],
+                "canonical_url": null,
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn test_puller(server: &MockServer) -> Result<ForemPuller> {
+        Ok(ForemPuller::new(
+            ForemInstance::DevTo,
+            "test-key".to_string(),
+            DEFAULT_API_VERSION,
+            &HttpOptions::default(),
+            None,
+        )?
+        .with_base_url(server.uri()))
+    }
+
+    /// A quiet context that stops at the first failure, for tests to override.
+    fn test_context<'a>(puller: &'a ForemPuller, writer: &'a Writer<'a>) -> PullContext<'a> {
+        PullContext {
+            puller,
+            writer,
+            force: false,
+            continue_on_error: false,
+            convert_embeds: false,
+            html_to_markdown: false,
+            include_stats: false,
+            include_comments: false,
+            dedupe_by_canonical: false,
+            canonical_from_url: false,
+            max_body_bytes: None,
+            truncate_oversized: false,
+            print: PrintMode::Off,
+            progress: None,
+            log_to_stderr: false,
+            quiet: true,
+        }
+    }
+
+    fn queue(ids: &[&str]) -> Vec<QueuedArticle> {
+        ids.iter()
+            .map(|id| QueuedArticle {
+                target: Target::Id((*id).to_string()),
+                label: format!("article {id}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_continue_on_error_collects_failures(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_article(&server, 1).await;
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        mount_article(&server, 3).await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let context = PullContext {
+            continue_on_error: true,
+            ..test_context(&puller, &writer)
+        };
+        let state = SharedPullState::default();
+        let mut manifest = Manifest::default();
+
+        let report = context
+            .pull_queue(&queue(&["1", "2", "3"]), &state, &mut manifest)
+            .await?;
+
+        assert_eq!(report.created(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "article 2");
+        assert_eq!(report.pulled[1].label, "article 3");
+        assert_eq!(report.pulled[1].path, "2024-03-15-article-3.md");
+        assert!(state.lock().is_pulled("devto:1"));
+        assert!(!state.lock().is_pulled("devto:2"));
+        assert!(state.lock().is_pulled("devto:3"));
+        Ok(())
+    }
+
+    /// Fails to render the frontmatter of article 1, which real articles hardly do.
+    struct FailingFrontmatter;
+
+    impl FrontmatterWriter for FailingFrontmatter {
+        fn render(&self, article: &PulledArticle, options: &FrontmatterOptions) -> Result<String> {
+            if article.platform_id == "1" {
+                return Err(PullError::InvalidConfig("unserializable".to_string()));
+            }
+            StandardWriter.render(article, options)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_failure_is_reported() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let server = MockServer::start().await;
+        mount_article(&server, 1).await;
+        mount_article(&server, 2).await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_frontmatter_writer(&FailingFrontmatter);
+        let context = PullContext {
+            continue_on_error: true,
+            ..test_context(&puller, &writer)
+        };
+        let state = SharedPullState::default();
+
+        let report = context
+            .pull_queue(&queue(&["1", "2"]), &state, &mut Manifest::default())
+            .await?;
+
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "article 1");
+        assert!(matches!(
+            &report.failed[0].1,
+            PullError::Render { title, .. } if title == "Article 1"
+        ));
+        assert_eq!(report.created(), 1);
+        assert!(dir.path().join("2024-03-15-article-2.md").exists());
+        assert!(!state.lock().is_pulled("devto:1"));
+        assert!(state.lock().is_pulled("devto:2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failure_aborts_without_continue_on_error(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let context = PullContext {
+            ..test_context(&puller, &writer)
+        };
+
+        let result = context
+            .pull_queue(
+                &queue(&["2"]),
+                &SharedPullState::default(),
+                &mut Manifest::default(),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PullError::Request { source, .. }) if matches!(*source, PullError::Api { status: 500, .. })
+        ));
+        Ok(())
+    }
+
+    async fn mount_cross_post(server: &MockServer, id: u64, canonical_url: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/articles/{id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": id,
+                "title": format!("Article {id}"),
+                "body_markdown": "Body",
+                "published_at": "2024-03-15T10:00:00Z",
+                "url": format!("https://dev.to/user/article-{id}"),
+                "tags": [],
+                "canonical_url": canonical_url,
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_by_canonical_skips_cross_posts(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_cross_post(&server, 1, "https://blog.example.com/post").await;
+        mount_cross_post(&server, 2, "http://www.blog.example.com/post/?utm_source=x").await;
+        mount_cross_post(&server, 3, "https://blog.example.com/other").await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let context = PullContext {
+            dedupe_by_canonical: true,
+            ..test_context(&puller, &writer)
+        };
+        let state = SharedPullState::default();
+
+        let report = context
+            .pull_queue(&queue(&["1", "2", "3"]), &state, &mut Manifest::default())
+            .await?;
+
+        assert_eq!(report.created(), 2);
+        assert_eq!(report.skipped_for(SkipReason::Duplicate), 1);
+        assert_eq!(report.skipped[0].label, "article 2");
+        assert_eq!(report.skipped[0].path, "2024-03-15-article-1.md");
+        assert!(state.lock().is_pulled("devto:1"));
+        assert!(!state.lock().is_pulled("devto:2"));
+        assert!(state.lock().is_pulled("devto:3"));
+
+        // Re-pulling the original itself is not a duplicate of its own entry
+        let report = context
+            .pull_queue(&queue(&["1"]), &state, &mut Manifest::default())
+            .await?;
+        assert!(report.skipped.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_canonical_from_url() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_article(&server, 1).await;
+        mount_cross_post(&server, 2, "https://blog.example.com/post").await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let context = PullContext {
+            canonical_from_url: true,
+            ..test_context(&puller, &writer)
+        };
+
+        context
+            .pull_queue(
+                &queue(&["1", "2"]),
+                &SharedPullState::default(),
+                &mut Manifest::default(),
+            )
+            .await?;
+
+        let own = std::fs::read_to_string(dir.path().join("2024-03-15-article-1.md"))?;
+        assert!(own.contains("canonical_url: https://dev.to/user/article-1\n"));
+        // An explicit canonical URL is kept
+        let cross_post = std::fs::read_to_string(dir.path().join("2024-03-15-article-2.md"))?;
+        assert!(cross_post.contains("canonical_url: https://blog.example.com/post\n"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_body_bytes() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_article(&server, 1).await;
+        Mock::given(method("GET"))
+            .and(path("/articles/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": 2,
+                "title": "Article 2",
+                "body_markdown": "Café ".repeat(10),
+                "published_at": "2024-03-15T10:00:00Z",
+                "url": "https://dev.to/user/article-2",
+                "tags": [],
+                "canonical_url": null,
+            })))
+            .mount(&server)
+            .await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let mut context = PullContext {
+            max_body_bytes: Some(22),
+            ..test_context(&puller, &writer)
+        };
+        let state = SharedPullState::default();
+
+        let report = context
+            .pull_queue(&queue(&["1", "2"]), &state, &mut Manifest::default())
+            .await?;
+        assert_eq!(report.created(), 1);
+        assert_eq!(report.skipped_for(SkipReason::Oversized), 1);
+        assert_eq!(report.skipped[0].label, "article 2");
+        assert_eq!(report.summary().oversized, 1);
+        assert!(!dir.path().join("2024-03-15-article-2.md").exists());
+        assert!(!state.lock().is_pulled("devto:2"));
+
+        context.truncate_oversized = true;
+        let report = context
+            .pull_queue(&queue(&["2"]), &state, &mut Manifest::default())
+            .await?;
+        assert_eq!(report.created(), 1);
+        assert_eq!(report.truncated, 1);
+        assert!(report.skipped.is_empty());
+        // Cut before the "é" straddling the limit
+        let content = std::fs::read_to_string(dir.path().join("2024-03-15-article-2.md"))?;
+        assert!(content.contains(
+            "Café Café Café Caf\n\n*[Truncated: the original body is 60 bytes, over the 22-byte limit]*"
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_print_article() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        mount_article(&server, 1).await;
+
+        let puller = test_puller(&server)?;
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), true, FolderStructure::Flat);
+        let mut context = PullContext {
+            log_to_stderr: true,
+            ..test_context(&puller, &writer)
+        };
+        let outcome =
+            writer.write_article(&puller.fetch_article("1").await?, &mut PullState::default())?;
+        let (path, content) = (&outcome.path, &outcome.content);
+
+        let mut out = Vec::new();
+        context.print_article(&mut out, path, content)?;
+        assert!(out.is_empty());
+
+        // A single article as the file reads, ready to pipe
+        context.print = PrintMode::Single;
+        context.print_article(&mut out, path, content)?;
+        assert_eq!(String::from_utf8(out)?, *content);
+
+        let mut out = Vec::new();
+        context.print = PrintMode::WithHeaders;
+        context.print_article(&mut out, path, content)?;
+        context.print_article(&mut out, "other.md", content)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            format!("==> 2024-03-15-article-1.md <==\n{content}\n==> other.md <==\n{content}\n")
+        );
+        assert!(content.starts_with("---\ntitle: Article 1\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_summary() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let pulled = |path: &str, status| PulledFile {
+            label: path.to_string(),
+            path: path.to_string(),
+            status,
+        };
+        let skipped = |reason| SkippedArticle {
+            label: "x".to_string(),
+            path: "x.md".to_string(),
+            reason,
+        };
+        let report = PullReport {
+            pulled: vec![
+                pulled("a.md", WriteStatus::Created),
+                pulled("b.md", WriteStatus::Unchanged),
+                pulled("c.md", WriteStatus::Updated),
+            ],
+            skipped: vec![
+                skipped(SkipReason::AlreadyPulled),
+                skipped(SkipReason::Duplicate),
+                skipped(SkipReason::Oversized),
+            ],
+            failed: vec![("d".to_string(), PullError::NotFound("d".to_string()))],
+            series: BTreeSet::new(),
+            skipped_pages: vec![4],
+            truncated: 2,
+        };
+
+        assert_eq!(
+            serde_json::to_value(report.summary())?,
+            json!({
+                "pulled": 3,
+                "skipped": 1,
+                "duplicates": 1,
+                "oversized": 1,
+                "truncated": 2,
+                "failed": 1,
+                "skipped_pages": [4],
+                "written": ["a.md", "c.md"],
+            })
+        );
+        assert_eq!(
+            report.totals(),
+            "Pulled: 3 (new: 1, updated: 1, unchanged: 1), Skipped: 1"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! Masks API keys in text that may end up in errors or logs.
+
+use std::ops::Range;
+
+/// Replacement for a redacted secret.
+pub const MASK: &str = "***";
+
+/// Names under which API keys are sent, as headers or query parameters (lowercase).
+///
+/// Ghost's bare `key` is only matched as a query parameter, where it can't be part
+/// of a longer name.
+const SECRET_NAMES: [&str; 4] = ["api-key", "api_key", "?key", "&key"];
+
+/// Replaces the value of every `api-key`/`api_key` in `text` with `***`.
+///
+/// Covers query parameters (`?api_key=...`, Ghost's `?key=...`), header lines
+/// (`api-key: ...`) and JSON fields (`"api-key": "..."`); names are matched
+/// case-insensitively.
+pub fn redact(text: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so matches index straight into `text`
+    let lower = text.to_ascii_lowercase();
+    let mut output = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut search_from = 0;
+
+    while let Some(name_end) = next_secret_name(&lower, search_from) {
+        search_from = name_end;
+        let Some(value) = value_after(text, name_end) else {
+            continue;
+        };
+
+        output.push_str(&text[copied..value.start]);
+        output.push_str(MASK);
+        copied = value.end;
+        search_from = value.end;
+    }
+
+    output.push_str(&text[copied..]);
+    output
+}
+
+/// Returns the end offset of the first secret name at or after `from`.
+fn next_secret_name(lower: &str, from: usize) -> Option<usize> {
+    SECRET_NAMES
+        .iter()
+        .filter_map(|name| lower[from..].find(name).map(|i| from + i + name.len()))
+        .min()
+}
+
+/// Finds the value assigned to a secret name ending at `name_end`, if any.
+fn value_after(text: &str, name_end: usize) -> Option<Range<usize>> {
+    const QUOTES: &[char] = &['"', '\''];
+
+    let rest = text[name_end..].trim_start_matches(QUOTES).trim_start();
+    let rest = rest.strip_prefix(&['=', ':'][..])?;
+    let value = rest.trim_start().trim_start_matches(QUOTES);
+
+    let start = text.len() - value.len();
+    let len = value
+        .find(|c: char| c.is_whitespace() || "&\"',;)}]".contains(c))
+        .unwrap_or(value.len());
+    (len > 0).then_some(start..start + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_query_param() {
+        assert_eq!(
+            redact("https://dev.to/api/articles?api_key=abc123&page=2"),
+            "https://dev.to/api/articles?api_key=***&page=2"
+        );
+        assert_eq!(
+            redact("https://blog.example.com/ghost/api/content/posts/?key=abc123&page=2"),
+            "https://blog.example.com/ghost/api/content/posts/?key=***&page=2"
+        );
+        assert_eq!(redact("?page=1&key=abc123"), "?page=1&key=***");
+        assert_eq!(redact("?monkey=banana"), "?monkey=banana");
+    }
+
+    #[test]
+    fn test_redact_header_and_json() {
+        assert_eq!(redact("API-Key: abc123"), "API-Key: ***");
+        assert_eq!(
+            redact(r#"{"api-key": "abc123", "page": 1}"#),
+            r#"{"api-key": "***", "page": 1}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_leaves_other_text_alone() {
+        let text = "the api-key header is missing";
+        assert_eq!(redact(text), text);
+        assert_eq!(redact("api_key="), "api_key=");
+    }
+}
@@ -0,0 +1,96 @@
+//! Keeps generated file and folder names valid on every platform.
+//!
+//! Names come from titles, series and platform names (e.g. `custom:forem.example.com`),
+//! which may contain characters Windows rejects or spell a reserved device name.
+
+/// Characters Windows doesn't allow in file names.
+const RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names Windows reserves regardless of extension (`CON`, `con.md`, ...).
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Makes a single path component safe to create on Windows, macOS and Linux.
+///
+/// Reserved and control characters become `_`, trailing dots and spaces are dropped,
+/// and a reserved device name gets a `_` appended to its stem (`con.md` -> `con_.md`).
+pub fn sanitize_component(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || RESERVED_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let trimmed = replaced.trim_end_matches(['.', ' ']);
+
+    if trimmed.is_empty() {
+        return "_".to_string();
+    }
+
+    let (stem, extension) = trimmed
+        .split_once('.')
+        .map_or((trimmed, ""), |(stem, _)| (stem, &trimmed[stem.len()..]));
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.trim_end().eq_ignore_ascii_case(reserved))
+    {
+        format!("{stem}_{extension}")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Sanitizes every `/`-separated component of a relative path.
+pub fn sanitize_path(relative_path: &str) -> String {
+    relative_path
+        .split('/')
+        .map(sanitize_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_reserved_characters() {
+        assert_eq!(
+            sanitize_component("custom:forem.example.com"),
+            "custom_forem.example.com"
+        );
+        assert_eq!(sanitize_component("a<b>c|d?e*f\"g\\h"), "a_b_c_d_e_f_g_h");
+        assert_eq!(sanitize_component("tab\there"), "tab_here");
+    }
+
+    #[test]
+    fn test_sanitize_reserved_device_names() {
+        assert_eq!(sanitize_component("CON"), "CON_");
+        assert_eq!(sanitize_component("con.md"), "con_.md");
+        assert_eq!(sanitize_component("Lpt1.comments.md"), "Lpt1_.comments.md");
+        assert_eq!(sanitize_component("console.md"), "console.md");
+        assert_eq!(sanitize_component("2024-03-15-con.md"), "2024-03-15-con.md");
+    }
+
+    #[test]
+    fn test_sanitize_trailing_dots_and_empty() {
+        assert_eq!(sanitize_component("notes. "), "notes");
+        assert_eq!(sanitize_component("..."), "_");
+        assert_eq!(sanitize_component(""), "_");
+    }
+
+    #[test]
+    fn test_sanitize_path_keeps_separators() {
+        assert_eq!(sanitize_path("2024/03/con.md"), "2024/03/con_.md");
+        assert_eq!(
+            sanitize_path("custom:blog.example.com/post.md"),
+            "custom_blog.example.com/post.md"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Ordering of listed articles for `--sort` and `--order`.
+
+use std::cmp::Ordering;
+
+use clap::ValueEnum;
+
+use crate::adapters::ArticleMetadata;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    /// Publication date; undated drafts sort as the oldest
+    #[default]
+    Date,
+    /// Title, ignoring case
+    Title,
+    /// Platform article ID
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Sorts articles in place. The sort is stable, so ties keep the platform's order.
+pub fn sort_articles(articles: &mut [ArticleMetadata], key: SortKey, order: SortOrder) {
+    articles.sort_by(|a, b| {
+        let ordering = match key {
+            SortKey::Date => a.published_at.cmp(&b.published_at),
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortKey::Id => compare_ids(&a.id, &b.id),
+        };
+        match order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+}
+
+/// Compares numeric IDs by value so "9" sorts before "10".
+fn compare_ids(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forem::ForemInstance;
+    use crate::platform::Platform;
+
+    fn meta(id: &str, title: &str, published_at: Option<&str>) -> ArticleMetadata {
+        ArticleMetadata {
+            id: id.to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: title.to_string(),
+            published_at: published_at.and_then(|d| d.parse().ok()),
+            edited_at: None,
+            url: None,
+            slug: None,
+            is_draft: published_at.is_none(),
+        }
+    }
+
+    fn ids(articles: &[ArticleMetadata]) -> Vec<&str> {
+        articles.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    fn sample() -> Vec<ArticleMetadata> {
+        vec![
+            meta("9", "banana", Some("2024-02-01T10:00:00Z")),
+            meta("10", "Apple", Some("2024-03-01T10:00:00Z")),
+            meta("2", "cherry", None),
+        ]
+    }
+
+    #[test]
+    fn test_sort_by_date() {
+        let mut articles = sample();
+        sort_articles(&mut articles, SortKey::Date, SortOrder::Desc);
+        assert_eq!(ids(&articles), vec!["10", "9", "2"]);
+
+        sort_articles(&mut articles, SortKey::Date, SortOrder::Asc);
+        assert_eq!(ids(&articles), vec!["2", "9", "10"]);
+    }
+
+    #[test]
+    fn test_sort_by_title_ignores_case() {
+        let mut articles = sample();
+        sort_articles(&mut articles, SortKey::Title, SortOrder::Asc);
+        assert_eq!(ids(&articles), vec!["10", "9", "2"]);
+    }
+
+    #[test]
+    fn test_sort_by_numeric_id() {
+        let mut articles = sample();
+        sort_articles(&mut articles, SortKey::Id, SortOrder::Asc);
+        assert_eq!(ids(&articles), vec!["2", "9", "10"]);
+    }
+}
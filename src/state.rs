@@ -1,33 +1,163 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::error::Result;
+use crate::error::{PullError, Result};
+use crate::forem::ForemInstance;
+use crate::platform::Platform;
 
 const STATE_FILENAME: &str = ".puller-state.json";
 
+/// Version of the state format written by this build.
+///
+/// 1. Files without a `version` field, whose entries may lack metadata
+/// 2. Every entry records its platform
+pub const STATE_VERSION: u32 = 2;
+
+/// Version of state files written before the format was versioned.
+const fn legacy_version() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PulledEntry {
     pub local_path: String,
     pub pulled_at: DateTime<Utc>,
+    /// `ETag` returned when the article was last fetched, if the platform sent one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// SHA-256 of the file as written, see `content_hash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Normalized URL of the original article, see `PulledArticle::canonical_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    /// Title at the time of the pull; empty for entries from before it was recorded
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_platform")]
+    pub platform: Platform,
+}
+
+/// Stand-in platform for entries recorded before the platform was stored.
+/// `PullState::load` replaces it with the one encoded in the entry's key.
+fn default_platform() -> Platform {
+    Platform::Forem(ForemInstance::DevTo)
+}
+
+/// Recovers the platform from a state key, `{platform}:{id}`.
+///
+/// Platform names contain colons themselves (`forem:custom:example.com`,
+/// `rss:https://...`), so the shortest prefix that parses as a platform wins.
+fn platform_from_key(key: &str) -> Option<Platform> {
+    key.match_indices(':')
+        .find_map(|(end, _)| key[..end].parse().ok())
+}
+
+/// Hex-encoded SHA-256 of a file's content, stored to detect later local changes.
+pub fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// Source of the current time for `pulled_at`, see `PullState::with_clock`.
+pub type Clock = fn() -> DateTime<Utc>;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PullState {
+    #[serde(default = "legacy_version")]
+    pub version: u32,
     pub pulled: HashMap<String, PulledEntry>,
+    #[serde(skip, default = "system_clock")]
+    clock: Clock,
+}
+
+fn system_clock() -> Clock {
+    Utc::now
+}
+
+impl Default for PullState {
+    fn default() -> Self {
+        Self::with_clock(system_clock())
+    }
 }
 
 impl PullState {
+    /// Creates an empty state whose `pulled_at` stamps come from `clock`, so tests
+    /// can pin the time.
+    pub fn with_clock(clock: Clock) -> Self {
+        Self {
+            version: STATE_VERSION,
+            pulled: HashMap::new(),
+            clock,
+        }
+    }
+
+    /// Loads the state, upgrading an older format in memory.
     pub fn load(output_dir: &Path) -> Result<Self> {
+        let Some(mut state) = Self::read(output_dir)? else {
+            return Ok(Self::default());
+        };
+        state.migrate()?;
+        Ok(state)
+    }
+
+    /// Upgrades the state file in place, returning the version it had if it
+    /// was older than `STATE_VERSION`.
+    pub fn migrate_file(output_dir: &Path) -> Result<Option<u32>> {
+        let Some(mut state) = Self::read(output_dir)? else {
+            return Err(PullError::InvalidConfig(format!(
+                "No {STATE_FILENAME} in {}",
+                output_dir.display()
+            )));
+        };
+        let from = state.version;
+        state.migrate()?;
+        if from == STATE_VERSION {
+            return Ok(None);
+        }
+        state.save(output_dir)?;
+        Ok(Some(from))
+    }
+
+    fn read(output_dir: &Path) -> Result<Option<Self>> {
         let state_path = output_dir.join(STATE_FILENAME);
-        if state_path.exists() {
-            let content = std::fs::read_to_string(&state_path)?;
-            Ok(serde_json::from_str(&content)?)
-        } else {
-            Ok(Self::default())
+        if !state_path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&state_path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        if self.version > STATE_VERSION {
+            return Err(PullError::InvalidConfig(format!(
+                "{STATE_FILENAME} is version {}, newer than this puller reads; upgrade puller",
+                self.version
+            )));
+        }
+
+        if self.version < 2 {
+            // Older entries carry no metadata; their platform is still in the key
+            for (key, entry) in &mut self.pulled {
+                if entry.title.is_empty() {
+                    if let Some(platform) = platform_from_key(key) {
+                        entry.platform = platform;
+                    }
+                }
+            }
         }
+
+        self.version = STATE_VERSION;
+        Ok(())
     }
 
     pub fn save(&self, output_dir: &Path) -> Result<()> {
@@ -41,21 +171,143 @@ impl PullState {
         self.pulled.contains_key(platform_id)
     }
 
-    pub fn mark_pulled(&mut self, platform_id: String, local_path: String) {
+    pub fn mark_pulled(
+        &mut self,
+        platform_id: String,
+        local_path: String,
+        title: String,
+        published_at: Option<DateTime<Utc>>,
+        platform: Platform,
+    ) {
         self.pulled.insert(
             platform_id,
             PulledEntry {
                 local_path,
-                pulled_at: Utc::now(),
+                pulled_at: (self.clock)(),
+                etag: None,
+                content_hash: None,
+                canonical_url: None,
+                title,
+                published_at,
+                platform,
             },
         );
     }
 
+    /// Records a file found on disk rather than a fresh pull, so only what the
+    /// key tells about the article is known.
+    pub fn mark_pulled_at(
+        &mut self,
+        platform_id: String,
+        local_path: String,
+        pulled_at: DateTime<Utc>,
+    ) {
+        let platform = platform_from_key(&platform_id).unwrap_or_else(default_platform);
+        self.pulled.insert(
+            platform_id,
+            PulledEntry {
+                local_path,
+                pulled_at,
+                etag: None,
+                content_hash: None,
+                canonical_url: None,
+                title: String::new(),
+                published_at: None,
+                platform,
+            },
+        );
+    }
+
+    /// Returns when an article from `platform` was last pulled, for `--since-last-pull`.
+    pub fn last_pulled_at(&self, platform: &Platform) -> Option<DateTime<Utc>> {
+        self.pulled
+            .values()
+            .filter(|entry| entry.platform == *platform)
+            .map(|entry| entry.pulled_at)
+            .max()
+    }
+
+    /// Returns true when the article was edited remotely after it was last pulled.
+    ///
+    /// Articles without an `edited_at` timestamp are never considered outdated.
+    pub fn is_outdated(&self, platform_id: &str, edited_at: Option<DateTime<Utc>>) -> bool {
+        match (self.pulled.get(platform_id), edited_at) {
+            (Some(entry), Some(edited_at)) => edited_at > entry.pulled_at,
+            _ => false,
+        }
+    }
+
+    pub fn set_etag(&mut self, platform_id: &str, etag: Option<String>) {
+        if let Some(entry) = self.pulled.get_mut(platform_id) {
+            entry.etag = etag;
+        }
+    }
+
+    pub fn set_content_hash(&mut self, platform_id: &str, content_hash: Option<String>) {
+        if let Some(entry) = self.pulled.get_mut(platform_id) {
+            entry.content_hash = content_hash;
+        }
+    }
+
+    pub fn set_canonical_url(&mut self, platform_id: &str, canonical_url: Option<String>) {
+        if let Some(entry) = self.pulled.get_mut(platform_id) {
+            entry.canonical_url = canonical_url;
+        }
+    }
+
+    /// Finds an article other than `platform_id` that was pulled with the same
+    /// canonical URL, returning its platform id and entry.
+    pub fn find_canonical(
+        &self,
+        canonical_url: &str,
+        platform_id: &str,
+    ) -> Option<(&str, &PulledEntry)> {
+        self.pulled
+            .iter()
+            .filter(|(id, _)| id.as_str() != platform_id)
+            .find(|(_, entry)| entry.canonical_url.as_deref() == Some(canonical_url))
+            .map(|(id, entry)| (id.as_str(), entry))
+    }
+
+    /// Finds the article recorded as written to `local_path`.
+    pub fn find_by_path(&self, local_path: &str) -> Option<&str> {
+        self.pulled
+            .iter()
+            .find(|(_, entry)| entry.local_path == local_path)
+            .map(|(id, _)| id.as_str())
+    }
+
+    pub fn get_etag(&self, platform_id: &str) -> Option<&str> {
+        self.pulled.get(platform_id).and_then(|e| e.etag.as_deref())
+    }
+
     pub fn get_local_path(&self, platform_id: &str) -> Option<&str> {
         self.pulled.get(platform_id).map(|e| e.local_path.as_str())
     }
 }
 
+/// A `PullState` shared between concurrent pull tasks.
+///
+/// Clones refer to the same state. Each `lock` holds it until the guard is
+/// dropped, which has to happen before the next `.await` so other tasks aren't
+/// blocked while one waits on the network.
+#[derive(Debug, Clone, Default)]
+pub struct SharedPullState(Arc<Mutex<PullState>>);
+
+impl SharedPullState {
+    pub fn new(state: PullState) -> Self {
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    /// Locks the state for reading or updating it.
+    ///
+    /// Entries are inserted whole, so a task that panicked while holding the
+    /// lock can't have left one half-written; a poisoned lock is taken over.
+    pub fn lock(&self) -> MutexGuard<'_, PullState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,20 +316,82 @@ mod tests {
     #[test]
     fn test_state_roundtrip() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let dir = TempDir::new()?;
-        let mut state = PullState::default();
+        let mut state = PullState::with_clock(|| {
+            DateTime::parse_from_rfc3339("2024-03-20T10:00:00Z")
+                .expect("valid timestamp")
+                .with_timezone(&Utc)
+        });
         state.mark_pulled(
-            "devto:123".to_string(),
+            "forem:vibe:123".to_string(),
             "2024-03-15-test-article.md".to_string(),
+            "Test Article".to_string(),
+            Some("2024-03-15T10:00:00Z".parse()?),
+            Platform::Forem(ForemInstance::Vibe),
         );
 
         state.save(dir.path())?;
 
         let loaded = PullState::load(dir.path())?;
-        assert!(loaded.is_pulled("devto:123"));
+        assert!(loaded.is_pulled("forem:vibe:123"));
         assert_eq!(
-            loaded.get_local_path("devto:123"),
+            loaded.get_local_path("forem:vibe:123"),
             Some("2024-03-15-test-article.md")
         );
+        let entry = &loaded.pulled["forem:vibe:123"];
+        assert_eq!(entry.title, "Test Article");
+        assert_eq!(entry.published_at, Some("2024-03-15T10:00:00Z".parse()?));
+        assert_eq!(
+            entry.pulled_at,
+            "2024-03-20T10:00:00Z".parse::<DateTime<Utc>>()?
+        );
+        assert_eq!(entry.platform, Platform::Forem(ForemInstance::Vibe));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_outdated() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut state = PullState::default();
+        state.pulled.insert(
+            "devto:123".to_string(),
+            PulledEntry {
+                local_path: "2024-03-15-test-article.md".to_string(),
+                pulled_at: "2024-03-20T10:00:00Z".parse()?,
+                etag: None,
+                content_hash: None,
+                canonical_url: None,
+                title: "Test Article".to_string(),
+                published_at: None,
+                platform: Platform::Forem(ForemInstance::DevTo),
+            },
+        );
+
+        assert!(state.is_outdated("devto:123", Some("2024-03-21T10:00:00Z".parse()?)));
+        assert!(!state.is_outdated("devto:123", Some("2024-03-19T10:00:00Z".parse()?)));
+        assert!(!state.is_outdated("devto:123", None));
+        assert!(!state.is_outdated("devto:456", Some("2024-03-21T10:00:00Z".parse()?)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_etag_roundtrip() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let mut state = PullState::default();
+        state.mark_pulled_at("devto:123".to_string(), "a.md".to_string(), Utc::now());
+        state.set_etag("devto:123", Some("W/\"abc\"".to_string()));
+        state.save(dir.path())?;
+
+        let loaded = PullState::load(dir.path())?;
+        assert_eq!(loaded.get_etag("devto:123"), Some("W/\"abc\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_state_without_etag() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let json =
+            r#"{"pulled":{"devto:1":{"local_path":"a.md","pulled_at":"2024-03-20T10:00:00Z"}}}"#;
+        let state: PullState = serde_json::from_str(json)?;
+        assert!(state.is_pulled("devto:1"));
+        assert_eq!(state.get_etag("devto:1"), None);
         Ok(())
     }
 
@@ -88,4 +402,140 @@ mod tests {
         assert!(state.pulled.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash("hello\n"),
+            "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03"
+        );
+    }
+
+    #[test]
+    fn test_load_state_without_metadata() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::write(
+            dir.path().join(STATE_FILENAME),
+            r#"{"pulled":{
+                "forem:custom:example.com:1":{"local_path":"a.md","pulled_at":"2024-03-20T10:00:00Z"},
+                "rss:https://blog.example.com/feed.xml:https://blog.example.com/?p=2":{"local_path":"b.md","pulled_at":"2024-03-20T10:00:00Z"}
+            }}"#,
+        )?;
+
+        let state = PullState::load(dir.path())?;
+        let custom = &state.pulled["forem:custom:example.com:1"];
+        assert_eq!(custom.title, "");
+        assert_eq!(custom.published_at, None);
+        assert_eq!(custom.platform.to_string(), "forem:custom:example.com");
+        assert_eq!(
+            state.pulled["rss:https://blog.example.com/feed.xml:https://blog.example.com/?p=2"]
+                .platform,
+            Platform::Rss("https://blog.example.com/feed.xml".parse()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_pulled_at() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let devto = Platform::Forem(ForemInstance::DevTo);
+        let mut state = PullState::default();
+        assert_eq!(state.last_pulled_at(&devto), None);
+
+        state.mark_pulled_at(
+            "devto:1".to_string(),
+            "one.md".to_string(),
+            "2024-03-20T10:00:00Z".parse()?,
+        );
+        state.mark_pulled_at(
+            "devto:2".to_string(),
+            "two.md".to_string(),
+            "2024-03-22T10:00:00Z".parse()?,
+        );
+        state.mark_pulled_at(
+            "forem:vibe:3".to_string(),
+            "three.md".to_string(),
+            "2024-03-25T10:00:00Z".parse()?,
+        );
+
+        assert_eq!(
+            state.last_pulled_at(&devto),
+            Some("2024-03-22T10:00:00Z".parse()?)
+        );
+        assert_eq!(
+            state.last_pulled_at(&Platform::Forem(ForemInstance::Vibe)),
+            Some("2024-03-25T10:00:00Z".parse()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_legacy_state() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::write(
+            dir.path().join(STATE_FILENAME),
+            r#"{"pulled":{"forem:vibe:1":{"local_path":"a.md","pulled_at":"2024-03-20T10:00:00Z"}}}"#,
+        )?;
+
+        let state = PullState::load(dir.path())?;
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(
+            state.pulled["forem:vibe:1"].platform,
+            Platform::Forem(ForemInstance::Vibe)
+        );
+
+        assert_eq!(PullState::migrate_file(dir.path())?, Some(1));
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(STATE_FILENAME))?)?;
+        assert_eq!(saved["version"], STATE_VERSION);
+        assert_eq!(saved["pulled"]["forem:vibe:1"]["platform"], "vibe");
+
+        assert_eq!(PullState::migrate_file(dir.path())?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_newer_state() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        std::fs::write(
+            dir.path().join(STATE_FILENAME),
+            format!(r#"{{"version":{},"pulled":{{}}}}"#, STATE_VERSION + 1),
+        )?;
+
+        assert!(matches!(
+            PullState::load(dir.path()),
+            Err(PullError::InvalidConfig(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_state_concurrent_marks(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let state = SharedPullState::default();
+        let tasks: Vec<_> = (0..32)
+            .map(|task| {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    for article in 0..50 {
+                        state.lock().mark_pulled(
+                            format!("devto:{task}-{article}"),
+                            format!("{task}-{article}.md"),
+                            format!("Article {task}-{article}"),
+                            None,
+                            Platform::Forem(ForemInstance::DevTo),
+                        );
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await?;
+        }
+
+        let state = state.lock();
+        assert_eq!(state.pulled.len(), 32 * 50);
+        assert_eq!(state.get_local_path("devto:31-49"), Some("31-49.md"));
+        Ok(())
+    }
 }
@@ -0,0 +1,139 @@
+//! Checks an archive against its pull state, for `puller verify`.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::adopt::{collect_markdown_files, relative_path};
+use crate::article::{read_platform_id, DEFAULT_EXTENSION};
+use crate::error::Result;
+use crate::state::{content_hash, PullState};
+
+/// An article file that doesn't match the pull state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub platform_id: String,
+    /// Path relative to the output directory
+    pub path: String,
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Number of state entries whose file exists and matches its stored hash
+    pub verified: usize,
+    /// State entries whose file is gone
+    pub missing: Vec<Discrepancy>,
+    /// Files whose content no longer matches the hash stored when they were written
+    pub modified: Vec<Discrepancy>,
+    /// Files carrying a platform ID that no state entry points at
+    pub untracked: Vec<Discrepancy>,
+}
+
+impl VerifyReport {
+    /// Number of problems found.
+    pub fn discrepancies(&self) -> usize {
+        self.missing.len() + self.modified.len() + self.untracked.len()
+    }
+}
+
+/// Compares every state entry with its file under `output_dir`, then looks for
+/// article files the state doesn't know about.
+///
+/// Entries written before hashes were stored (or recorded by `adopt`) only have
+/// their file's existence checked.
+pub fn verify_directory(output_dir: &Path, state: &PullState) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+
+    let mut entries: Vec<_> = state.pulled.iter().collect();
+    entries.sort_by(|a, b| a.1.local_path.cmp(&b.1.local_path));
+
+    for (platform_id, entry) in entries {
+        let discrepancy = Discrepancy {
+            platform_id: platform_id.clone(),
+            path: entry.local_path.clone(),
+        };
+
+        let Ok(content) = std::fs::read_to_string(output_dir.join(&entry.local_path)) else {
+            report.missing.push(discrepancy);
+            continue;
+        };
+        match &entry.content_hash {
+            Some(hash) if *hash != content_hash(&content) => report.modified.push(discrepancy),
+            _ => report.verified += 1,
+        }
+    }
+
+    let tracked: HashSet<&str> = state
+        .pulled
+        .values()
+        .map(|entry| entry.local_path.as_str())
+        .collect();
+    let mut files = Vec::new();
+    collect_markdown_files(output_dir, DEFAULT_EXTENSION, &mut files)?;
+    files.sort();
+
+    for path in files {
+        let relative_path = relative_path(output_dir, &path);
+        if tracked.contains(relative_path.as_str()) {
+            continue;
+        }
+        if let Some(platform_id) = read_platform_id(&std::fs::read_to_string(&path)?) {
+            report.untracked.push(Discrepancy {
+                platform_id,
+                path: relative_path,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    const ARTICLE: &str = "---\ntitle: Hello\n# Platform ID: devto:1\n---\n\nBody\n";
+
+    #[test]
+    fn test_verify_directory() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let mut state = PullState::default();
+
+        std::fs::write(dir.path().join("intact.md"), ARTICLE)?;
+        state.mark_pulled_at("devto:1".to_string(), "intact.md".to_string(), Utc::now());
+        state.set_content_hash("devto:1", Some(content_hash(ARTICLE)));
+
+        std::fs::write(dir.path().join("edited.md"), "tampered\n")?;
+        state.mark_pulled_at("devto:2".to_string(), "edited.md".to_string(), Utc::now());
+        state.set_content_hash("devto:2", Some(content_hash(ARTICLE)));
+
+        state.mark_pulled_at("devto:3".to_string(), "deleted.md".to_string(), Utc::now());
+
+        // Adopted entries have no hash, so only their file's existence is checked
+        std::fs::write(dir.path().join("adopted.md"), "anything\n")?;
+        state.mark_pulled_at("devto:4".to_string(), "adopted.md".to_string(), Utc::now());
+
+        std::fs::write(
+            dir.path().join("stray.md"),
+            ARTICLE.replace("devto:1", "devto:5"),
+        )?;
+        std::fs::write(dir.path().join("notes.md"), "# Just notes\n")?;
+
+        let report = verify_directory(dir.path(), &state)?;
+
+        assert_eq!(report.verified, 2);
+        let paths = |list: &[Discrepancy]| list.iter().map(|d| d.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&report.missing), vec!["deleted.md"]);
+        assert_eq!(paths(&report.modified), vec!["edited.md"]);
+        assert_eq!(
+            report.untracked,
+            vec![Discrepancy {
+                platform_id: "devto:5".to_string(),
+                path: "stray.md".to_string(),
+            }]
+        );
+        assert_eq!(report.discrepancies(), 3);
+        Ok(())
+    }
+}
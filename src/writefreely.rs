@@ -0,0 +1,148 @@
+//! WriteFreely instance definitions: the hosted write.as service, or any
+//! self-hosted WriteFreely instance.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+use crate::error::PullError;
+
+/// Where a WriteFreely blog is hosted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteFreelyInstance {
+    /// write.as, the hosted WriteFreely service
+    WriteAs,
+    /// Self-hosted WriteFreely instance with a specified domain
+    Custom { domain: String },
+}
+
+impl WriteFreelyInstance {
+    /// Returns the site URL of this instance, without a trailing slash.
+    #[must_use]
+    pub fn site_url(&self) -> String {
+        match self {
+            Self::WriteAs => "https://write.as".to_string(),
+            Self::Custom { domain } => format!("https://{domain}"),
+        }
+    }
+
+    /// Returns the API base URL for this instance.
+    #[must_use]
+    pub fn base_url(&self) -> String {
+        format!("{}/api", self.site_url())
+    }
+
+    /// Detects the instance from a post or blog URL's host; any host other than
+    /// write.as becomes `Custom`. Returns `None` only for URLs without a host.
+    #[must_use]
+    pub fn from_url(url: &Url) -> Option<Self> {
+        let host = url.host_str()?.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if host == "write.as" {
+            return Some(Self::WriteAs);
+        }
+
+        let domain = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        Some(Self::Custom { domain })
+    }
+
+    /// Returns the short identifier for this instance (used in platform strings).
+    #[must_use]
+    pub fn as_str(&self) -> String {
+        match self {
+            Self::WriteAs => "writeas".to_string(),
+            Self::Custom { domain } => format!("custom:{domain}"),
+        }
+    }
+}
+
+impl fmt::Display for WriteFreelyInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for WriteFreelyInstance {
+    type Err = PullError;
+
+    /// Parse a WriteFreely instance from a string.
+    ///
+    /// Supported formats:
+    /// - "writeas" or "write.as" -> WriteAs
+    /// - "custom:example.com" -> Custom { domain: "example.com" }
+    /// - a host or URL such as "https://blog.example.com" -> Custom for its host
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+
+        if let Some(domain) = lower.strip_prefix("custom:") {
+            if domain.is_empty() {
+                return Err(PullError::UnsupportedPlatform(
+                    "Custom WriteFreely instance requires a domain".to_string(),
+                ));
+            }
+            return Ok(Self::Custom {
+                domain: domain.to_string(),
+            });
+        }
+
+        if matches!(lower.as_str(), "writeas" | "write.as") {
+            return Ok(Self::WriteAs);
+        }
+
+        let url = if lower.contains("://") {
+            Url::parse(&lower).ok()
+        } else if lower.contains('.') {
+            Url::parse(&format!("https://{lower}")).ok()
+        } else {
+            None
+        };
+        url.as_ref().and_then(Self::from_url).ok_or_else(|| {
+            PullError::UnsupportedPlatform(format!("Unknown WriteFreely instance: {s}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(
+            WriteFreelyInstance::WriteAs.base_url(),
+            "https://write.as/api"
+        );
+        let custom = WriteFreelyInstance::Custom {
+            domain: "blog.example.com".to_string(),
+        };
+        assert_eq!(custom.base_url(), "https://blog.example.com/api");
+    }
+
+    #[test]
+    fn test_from_str() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "write.as".parse::<WriteFreelyInstance>()?,
+            WriteFreelyInstance::WriteAs
+        );
+        let custom = WriteFreelyInstance::Custom {
+            domain: "blog.example.com".to_string(),
+        };
+        assert_eq!(
+            "custom:Blog.Example.com".parse::<WriteFreelyInstance>()?,
+            custom
+        );
+        assert_eq!(
+            "https://blog.example.com/alice/".parse::<WriteFreelyInstance>()?,
+            custom
+        );
+        assert_eq!(custom.to_string().parse::<WriteFreelyInstance>()?, custom);
+        assert!("custom:".parse::<WriteFreelyInstance>().is_err());
+        assert!("nonsense".parse::<WriteFreelyInstance>().is_err());
+        Ok(())
+    }
+}
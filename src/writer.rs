@@ -1,22 +1,143 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
 use std::path::Path;
 
 use clap::ValueEnum;
+use futures::stream::{self, StreamExt};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use similar::TextDiff;
+use tracing::warn;
+use url::Url;
 
-use crate::article::PulledArticle;
-use crate::error::Result;
-use crate::state::PullState;
+use crate::article::{
+    read_frontmatter_title, slugify, PulledArticle, DEFAULT_EXTENSION, DEFAULT_MAX_SLUG_LEN,
+};
+use crate::comments::{comments_to_markdown, Comment, COMMENTS_SUFFIX};
+use crate::error::{PullError, Result};
+use crate::frontmatter::{FrontmatterFormat, FrontmatterOptions, FrontmatterWriter};
+use crate::images::{
+    find_image_urls, image_extension, image_stem, replace_image_url, UNKNOWN_IMAGE_EXTENSION,
+};
+use crate::sanitize::{sanitize_component, sanitize_path};
+use crate::state::{content_hash, PullState};
+
+/// Folder that drafts are written to under `FolderStructure::DateHierarchy`.
+const DRAFTS_DIR: &str = "drafts";
+/// Folder for articles without tags under `FolderStructure::Tag`
+const UNTAGGED_DIR: &str = "untagged";
+/// Folders of published posts and of drafts under `FolderStructure::Jekyll`
+const JEKYLL_POSTS_DIR: &str = "_posts";
+const JEKYLL_DRAFTS_DIR: &str = "_drafts";
+
+/// Index file generated in each series folder under `FolderStructure::Series`.
+pub const SERIES_INDEX_FILENAME: &str = "_index.md";
+
+/// Images of one article downloaded at the same time.
+const IMAGE_DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// Appended to the name of a file moved aside by `OverwriteMode::Backup`.
+const BACKUP_SUFFIX: &str = ".bak";
 
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 pub enum FolderStructure {
     #[default]
     Platform, // content/devto/article.md
-    Flat, // content/article.md
+    Flat,          // content/article.md
+    DateHierarchy, // content/2024/03/article.md, content/drafts/article.md
+    Series,        // content/my-series/article.md, standalone posts in content/
+    HugoBundle,    // content/2024-03-15-article/index.md, images alongside
+    Tag,           // content/rust/article.md (first tag only), content/untagged/article.md
+    Jekyll,        // content/_posts/2024-03-15-article.md, content/_drafts/article.md
+}
+
+/// What happened to an article's file during `Writer::write_article`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteStatus {
+    /// The file did not exist and was created
+    Created,
+    /// The file existed with different content and was overwritten
+    Updated,
+    /// The file already had identical content
+    Unchanged,
+    /// Dry-run mode; the file doesn't exist yet and nothing was written
+    WouldWrite,
+    /// Dry-run mode; the file exists with different content, see `WriteOutcome::diff`
+    WouldUpdate,
+    /// The file had different content and was left alone, see `OverwriteMode`
+    Skipped,
+}
+
+/// What `Writer::write_article` does with an existing file whose content differs.
+///
+/// Files with identical content are never rewritten, whatever the mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OverwriteMode {
+    /// Keep the file and report `WriteStatus::Skipped`
+    Skip,
+    /// Replace the file
+    Overwrite,
+    /// Rename the file to `{name}.bak`, then write
+    Backup,
+    /// Replace the file unless it was edited since it was pulled, i.e. its
+    /// content no longer matches the hash in the state; edited files are kept
+    /// as with `Skip`. Files without a recorded hash are replaced.
+    #[default]
+    IfChanged,
+}
+
+/// Line endings of written files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LineEndings {
+    /// `\n` everywhere
+    #[default]
+    Lf,
+    /// `\r\n` everywhere
+    Crlf,
+    /// Keep the article body as the platform sent it
+    Preserve,
+}
+
+impl LineEndings {
+    /// Converts every line ending in `text`, frontmatter included.
+    pub fn apply(self, text: String) -> String {
+        match self {
+            Self::Lf => text.replace("\r\n", "\n"),
+            Self::Crlf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+            Self::Preserve => text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteOutcome {
+    /// Path of the article file relative to the output directory
+    pub path: String,
+    pub status: WriteStatus,
+    /// Unified diff from the existing file to the new content, for `WouldUpdate`
+    pub diff: Option<String>,
+    /// The rendered file, whether or not it was written
+    pub content: String,
 }
 
 pub struct Writer<'a> {
     output_dir: &'a Path,
     dry_run: bool,
     structure: FolderStructure,
+    max_slug_len: usize,
+    /// Extension of article files, without the dot
+    extension: String,
+    overwrite: OverwriteMode,
+    line_endings: LineEndings,
+    /// Client for `download_images`; `None` leaves images remote
+    image_client: Option<reqwest::Client>,
+    /// Whether files get the `# Platform ID` comment and an `id` key, and how
+    /// long a generated `slug` may be
+    frontmatter_options: FrontmatterOptions,
+    /// Frontmatter keys to write; `None` picks them from the folder structure
+    frontmatter: Option<FrontmatterFormat>,
+    /// Renders the frontmatter instead of the writer of `frontmatter`
+    frontmatter_writer: Option<&'a dyn FrontmatterWriter>,
 }
 
 impl<'a> Writer<'a> {
@@ -25,40 +146,428 @@ impl<'a> Writer<'a> {
             output_dir,
             dry_run,
             structure,
+            max_slug_len: DEFAULT_MAX_SLUG_LEN,
+            extension: DEFAULT_EXTENSION.to_string(),
+            overwrite: OverwriteMode::default(),
+            line_endings: LineEndings::default(),
+            image_client: None,
+            frontmatter_options: FrontmatterOptions::default(),
+            frontmatter: None,
+            frontmatter_writer: None,
         }
     }
 
-    pub fn write_article(&self, article: &PulledArticle, state: &mut PullState) -> Result<String> {
-        let filename = article.generate_filename();
-        let (filepath, relative_path) = match self.structure {
-            FolderStructure::Flat => (self.output_dir.join(&filename), filename),
-            FolderStructure::Platform => {
-                let platform_str = article.platform.to_string();
-                let platform_dir = self.output_dir.join(&platform_str);
-                (
-                    platform_dir.join(&filename),
-                    format!("{platform_str}/{filename}"),
-                )
+    /// Sets the maximum length of the slug part of generated filenames.
+    #[must_use]
+    pub fn with_max_slug_len(mut self, max_slug_len: usize) -> Self {
+        self.max_slug_len = max_slug_len;
+        self.frontmatter_options.max_slug_len = max_slug_len;
+        self
+    }
+
+    /// Sets the extension of article files (without the dot, see `parse_extension`).
+    #[must_use]
+    pub fn with_extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = extension.into();
+        self
+    }
+
+    /// Sets the line endings of every written file.
+    #[must_use]
+    pub fn with_line_endings(mut self, line_endings: LineEndings) -> Self {
+        self.line_endings = line_endings;
+        self
+    }
+
+    /// Enables `download_images`, fetching images with `client`.
+    #[must_use]
+    pub fn with_image_downloads(mut self, client: reqwest::Client) -> Self {
+        self.image_client = Some(client);
+        self
+    }
+
+    /// Leaves the `# Platform ID` comment out of written files when `false`.
+    #[must_use]
+    pub fn with_tracking_marker(mut self, tracking_marker: bool) -> Self {
+        self.frontmatter_options.tracking_marker = tracking_marker;
+        self
+    }
+
+    /// Writes the article's platform id as an `id` frontmatter key when `true`,
+    /// whether or not the `# Platform ID` comment is written too.
+    #[must_use]
+    pub fn with_id_in_frontmatter(mut self, include_id: bool) -> Self {
+        self.frontmatter_options.include_id = include_id;
+        self
+    }
+
+    /// Writes `format` frontmatter instead of the one matching the folder structure
+    /// (Hugo's for `HugoBundle`, Jekyll's for `Jekyll`, else the standard keys).
+    #[must_use]
+    pub fn with_frontmatter_format(mut self, format: Option<FrontmatterFormat>) -> Self {
+        self.frontmatter = format;
+        self
+    }
+
+    /// Renders frontmatter with `writer`, taking precedence over `with_frontmatter_format`.
+    #[must_use]
+    pub fn with_frontmatter_writer(mut self, writer: &'a dyn FrontmatterWriter) -> Self {
+        self.frontmatter_writer = Some(writer);
+        self
+    }
+
+    /// Sets how existing files with different content are handled.
+    #[must_use]
+    pub fn with_overwrite_mode(mut self, overwrite: OverwriteMode) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    pub fn write_article(
+        &self,
+        article: &PulledArticle,
+        state: &mut PullState,
+    ) -> Result<WriteOutcome> {
+        let relative_path = self.relative_path(article);
+        let filepath = self.output_dir.join(&relative_path);
+
+        let format = self.frontmatter.unwrap_or(match self.structure {
+            FolderStructure::HugoBundle => FrontmatterFormat::Hugo,
+            FolderStructure::Jekyll => FrontmatterFormat::Jekyll,
+            _ => FrontmatterFormat::Standard,
+        });
+        let frontmatter_writer = self.frontmatter_writer.unwrap_or_else(|| format.writer());
+        // Named after the article, so continue-on-error mode reports which one failed
+        let markdown = article
+            .to_markdown_with(frontmatter_writer, &self.frontmatter_options)
+            .map_err(|e| PullError::Render {
+                title: article.title.clone(),
+                source: Box::new(e),
+            })?;
+        let content = self.line_endings.apply(markdown);
+        let existing = std::fs::read_to_string(&filepath).ok();
+        let status = match &existing {
+            Some(existing) if *existing == content => WriteStatus::Unchanged,
+            Some(_) => WriteStatus::Updated,
+            None => WriteStatus::Created,
+        };
+
+        let platform_id = format!("{}:{}", article.platform, article.platform_id);
+        let keep_existing = match self.overwrite {
+            OverwriteMode::Skip => true,
+            OverwriteMode::Overwrite | OverwriteMode::Backup => false,
+            OverwriteMode::IfChanged => {
+                // Only a file recorded at this very path can be compared
+                let recorded = state
+                    .pulled
+                    .get(&platform_id)
+                    .filter(|entry| entry.local_path == relative_path)
+                    .and_then(|entry| entry.content_hash.as_deref());
+                match (recorded, &existing) {
+                    (Some(hash), Some(existing)) => content_hash(existing) != hash,
+                    _ => false,
+                }
             }
         };
+        if status == WriteStatus::Updated && keep_existing {
+            // State is left as is so the article is offered again next run
+            return Ok(WriteOutcome {
+                path: relative_path,
+                status: WriteStatus::Skipped,
+                diff: None,
+                content,
+            });
+        }
 
-        if !self.dry_run {
+        if self.dry_run {
+            let (status, diff) = match (status, existing) {
+                (WriteStatus::Updated, Some(existing)) => (
+                    WriteStatus::WouldUpdate,
+                    Some(unified_diff(&relative_path, &existing, &content)),
+                ),
+                (WriteStatus::Unchanged, _) => (WriteStatus::Unchanged, None),
+                _ => (WriteStatus::WouldWrite, None),
+            };
+            return Ok(WriteOutcome {
+                path: relative_path,
+                status,
+                diff,
+                content,
+            });
+        }
+
+        if status == WriteStatus::Updated && self.overwrite == OverwriteMode::Backup {
+            let mut backup_path = filepath.clone().into_os_string();
+            backup_path.push(BACKUP_SUFFIX);
+            std::fs::rename(&filepath, backup_path)?;
+        }
+
+        if status != WriteStatus::Unchanged {
             // Create subdirectory if needed
             if let Some(parent) = filepath.parent() {
                 std::fs::create_dir_all(parent)?;
             }
+            std::fs::write(&filepath, &content)?;
+        }
 
-            let content = article.to_markdown()?;
-            std::fs::write(&filepath, content)?;
+        state.mark_pulled(
+            platform_id.clone(),
+            relative_path.clone(),
+            article.title.clone(),
+            article.published_at,
+            article.platform.clone(),
+        );
+        state.set_etag(&platform_id, article.etag.clone());
+        state.set_content_hash(&platform_id, Some(content_hash(&content)));
+        state.set_canonical_url(&platform_id, article.canonical_key());
 
-            let platform_id = format!("{}:{}", article.platform, article.platform_id);
-            state.mark_pulled(platform_id, relative_path.clone());
+        Ok(WriteOutcome {
+            path: relative_path,
+            status,
+            diff: None,
+            content,
+        })
+    }
+
+    /// Writes an article's comments next to it as `{name}.comments.md`, whatever the
+    /// article extension.
+    ///
+    /// `article_path` is the relative path returned by `write_article`. Returns the
+    /// relative path of the comments file.
+    pub fn write_comments(
+        &self,
+        article_path: &str,
+        article: &PulledArticle,
+        comments: &[Comment],
+    ) -> Result<String> {
+        let stem = article_path
+            .strip_suffix(&format!(".{}", self.extension))
+            .unwrap_or(article_path);
+        let relative_path = format!("{stem}{COMMENTS_SUFFIX}");
+
+        if !self.dry_run {
+            std::fs::write(
+                self.output_dir.join(&relative_path),
+                self.line_endings
+                    .apply(comments_to_markdown(article, comments)),
+            )?;
         }
 
         Ok(relative_path)
     }
 
+    /// Downloads the article's remote images and points its body at the local copies.
+    ///
+    /// Images go into the bundle folder under `FolderStructure::HugoBundle`, and into
+    /// `images/{name}/` next to the article file otherwise. Images already on disk are
+    /// not downloaded again; one that fails to download keeps its remote URL. File
+    /// extensions follow the `Content-Type` of the response. Up to
+    /// `IMAGE_DOWNLOAD_CONCURRENCY` images are fetched at once, each distinct URL
+    /// only once. Does nothing in dry-run mode or without `with_image_downloads`.
+    /// Returns the number of images downloaded.
+    pub async fn download_images(&self, article: &mut PulledArticle) -> Result<usize> {
+        let Some(client) = &self.image_client else {
+            return Ok(0);
+        };
+        if self.dry_run {
+            return Ok(0);
+        }
+
+        let relative_path = self.relative_path(article);
+        let (article_dir, filename) = relative_path
+            .rsplit_once('/')
+            .unwrap_or(("", &relative_path));
+        let image_dir = match self.structure {
+            FolderStructure::HugoBundle => String::new(),
+            _ => {
+                let stem = filename
+                    .strip_suffix(&format!(".{}", self.extension))
+                    .unwrap_or(filename);
+                format!("images/{stem}/")
+            }
+        };
+
+        let dir = self.output_dir.join(article_dir).join(&image_dir);
+        let images: Vec<_> = find_image_urls(&article.body_markdown)
+            .into_iter()
+            .enumerate()
+            .map(|(index, link)| {
+                let stem = image_stem(&link.url, index);
+                let existing = existing_image(&dir, &stem);
+                (link, stem, existing)
+            })
+            .collect();
+
+        // `None` for images already on disk; results stay in the order of `images`
+        let fetched: Vec<_> = stream::iter(&images)
+            .map(|(link, _, existing)| async move {
+                match existing {
+                    Some(_) => None,
+                    None => Some(fetch_image(client, &link.url).await),
+                }
+            })
+            .buffered(IMAGE_DOWNLOAD_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut downloaded = 0;
+        for ((link, stem, existing), fetched) in images.iter().zip(fetched) {
+            let url = &link.url;
+            let name = match (existing, fetched) {
+                (Some(name), _) => name.clone(),
+                (None, Some(Ok(image))) => {
+                    let content_type = image.content_type.as_deref().unwrap_or_default();
+                    let extension = image_extension(content_type).unwrap_or_else(|| {
+                        warn!(%url, content_type, "unknown image type, saving as .{UNKNOWN_IMAGE_EXTENSION}");
+                        UNKNOWN_IMAGE_EXTENSION
+                    });
+                    let name = format!("{stem}.{extension}");
+                    std::fs::create_dir_all(&dir)?;
+                    std::fs::write(dir.join(&name), image.bytes)?;
+                    downloaded += 1;
+                    name
+                }
+                (None, Some(Err(e))) => {
+                    warn!(%url, "could not download image, keeping the remote link: {e}");
+                    continue;
+                }
+                (None, None) => continue,
+            };
+
+            let local = format!("{image_dir}{name}");
+            article.body_markdown = replace_image_url(&article.body_markdown, &link.text, &local);
+        }
+
+        Ok(downloaded)
+    }
+
+    /// Returns an article's file path relative to the output directory.
+    fn relative_path(&self, article: &PulledArticle) -> String {
+        let filename = match self.structure {
+            // Jekyll dates drafts when they're published, so their names carry no date
+            FolderStructure::Jekyll if is_jekyll_draft(article) => format!(
+                "{}.{}",
+                slugify(&article.title, self.max_slug_len),
+                self.extension
+            ),
+            _ => article.generate_filename(self.max_slug_len, &self.extension),
+        };
+        let path = match (self.structure, self.subdirectory(article)) {
+            (FolderStructure::HugoBundle, _) => {
+                let bundle = filename
+                    .strip_suffix(&format!(".{}", self.extension))
+                    .unwrap_or(&filename);
+                format!("{bundle}/index.{}", self.extension)
+            }
+            (_, Some(dir)) => format!("{dir}/{filename}"),
+            (_, None) => filename,
+        };
+        sanitize_path(&path)
+    }
+
+    /// Returns the folder (relative to the output directory) an article belongs in.
+    fn subdirectory(&self, article: &PulledArticle) -> Option<String> {
+        match self.structure {
+            FolderStructure::Flat | FolderStructure::HugoBundle => None,
+            // Feed platforms carry a whole URL, slashes included
+            FolderStructure::Platform => Some(sanitize_component(&article.platform.to_string())),
+            FolderStructure::DateHierarchy => {
+                let dir = match article.published_at {
+                    Some(dt) if !article.is_draft => dt.format("%Y/%m").to_string(),
+                    _ => DRAFTS_DIR.to_string(),
+                };
+                Some(dir)
+            }
+            FolderStructure::Series => article
+                .series
+                .as_ref()
+                .map(|series| slugify(series, self.max_slug_len)),
+            // One folder per article, so posts with several tags aren't duplicated
+            FolderStructure::Tag => Some(article.tags.first().map_or_else(
+                || UNTAGGED_DIR.to_string(),
+                |tag| slugify(tag, self.max_slug_len),
+            )),
+            FolderStructure::Jekyll => {
+                let dir = if is_jekyll_draft(article) {
+                    JEKYLL_DRAFTS_DIR
+                } else {
+                    JEKYLL_POSTS_DIR
+                };
+                Some(dir.to_string())
+            }
+        }
+    }
+
+    /// Writes an `_index.md` into the folder of each given series listing its posts.
+    ///
+    /// Posts are read back from the folder itself so articles pulled in earlier runs
+    /// are included; filenames start with the publish date, so sorting them gives
+    /// publication order (drafts last). Returns the relative paths of the indexes.
+    pub fn write_series_indexes(&self, series_names: &BTreeSet<String>) -> Result<Vec<String>> {
+        #[derive(Serialize)]
+        struct IndexFrontmatter<'a> {
+            title: &'a str,
+        }
+
+        let mut written = Vec::new();
+        if !matches!(self.structure, FolderStructure::Series) {
+            return Ok(written);
+        }
+
+        for series in series_names {
+            let dir_name = slugify(series, self.max_slug_len);
+            let relative_path = format!("{dir_name}/{SERIES_INDEX_FILENAME}");
+
+            if self.dry_run {
+                written.push(relative_path);
+                continue;
+            }
+
+            let series_dir = self.output_dir.join(&dir_name);
+            let article_suffix = format!(".{}", self.extension);
+            let mut filenames = Vec::new();
+            for entry in std::fs::read_dir(&series_dir)? {
+                let filename = entry?.file_name().to_string_lossy().into_owned();
+                if filename.ends_with(&article_suffix)
+                    && !filename.ends_with(COMMENTS_SUFFIX)
+                    && filename != SERIES_INDEX_FILENAME
+                {
+                    filenames.push(filename);
+                }
+            }
+            filenames.sort_by_key(|name| (name.starts_with("draft-"), name.clone()));
+
+            let mut content = String::from("---\n");
+            content.push_str(&serde_yaml::to_string(&IndexFrontmatter { title: series })?);
+            content.push_str("---\n\n");
+            for (position, filename) in filenames.iter().enumerate() {
+                let post = std::fs::read_to_string(series_dir.join(filename))?;
+                let title = read_frontmatter_title(&post).unwrap_or_else(|| filename.clone());
+                writeln!(content, "{}. [{title}]({filename})", position + 1)
+                    .expect("String write failed");
+            }
+
+            std::fs::write(
+                series_dir.join(SERIES_INDEX_FILENAME),
+                self.line_endings.apply(content),
+            )?;
+            written.push(relative_path);
+        }
+
+        Ok(written)
+    }
+
+    /// Creates the output directory unless in dry-run mode.
+    ///
+    /// Fails when a file is in its place, which would otherwise only show as an IO
+    /// error on the first article written.
     pub fn ensure_output_dir(&self) -> Result<()> {
+        if self.output_dir.exists() && !self.output_dir.is_dir() {
+            return Err(PullError::InvalidConfig(format!(
+                "Output path {} is not a directory",
+                self.output_dir.display()
+            )));
+        }
         if !self.dry_run && !self.output_dir.exists() {
             std::fs::create_dir_all(self.output_dir)?;
         }
@@ -66,12 +575,60 @@ impl<'a> Writer<'a> {
     }
 }
 
+/// Whether an article goes to `_drafts/` under `FolderStructure::Jekyll`: a post in
+/// `_posts/` needs the publish date its filename starts with.
+const fn is_jekyll_draft(article: &PulledArticle) -> bool {
+    article.is_draft || article.published_at.is_none()
+}
+
+/// An image response, with the `Content-Type` that decides its file extension.
+struct FetchedImage {
+    bytes: Vec<u8>,
+    content_type: Option<String>,
+}
+
+async fn fetch_image(client: &reqwest::Client, url: &Url) -> Result<FetchedImage> {
+    let response = client.get(url.clone()).send().await?.error_for_status()?;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    Ok(FetchedImage {
+        bytes: response.bytes().await?.to_vec(),
+        content_type,
+    })
+}
+
+/// Finds an image an earlier run saved as `stem`, whatever its extension.
+fn existing_image(dir: &Path, stem: &str) -> Option<String> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .find(|name| {
+            name.strip_prefix(stem)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+}
+
+/// Renders a unified diff between two versions of the file at `relative_path`.
+fn unified_diff(relative_path: &str, old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{relative_path}"), &format!("b/{relative_path}"))
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::article::ArticleStats;
     use crate::forem::ForemInstance;
     use crate::platform::Platform;
+    use std::sync::{Arc, Mutex};
     use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[test]
     fn test_write_article_flat() -> std::result::Result<(), Box<dyn std::error::Error>> {
@@ -85,14 +642,20 @@ mod tests {
             title: "Test Article".to_string(),
             body_markdown: "Hello, world!".to_string(),
             published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
             url: Some("https://dev.to/user/test-article".parse()?),
             tags: vec!["rust".to_string()],
             series: None,
             canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
             is_draft: false,
         };
 
-        let relative_path = writer.write_article(&article, &mut state)?;
+        let relative_path = writer.write_article(&article, &mut state)?.path;
         assert_eq!(relative_path, "2024-03-15-test-article.md");
 
         let filepath = dir.path().join(&relative_path);
@@ -117,14 +680,20 @@ mod tests {
             title: "Test Article".to_string(),
             body_markdown: "Hello, world!".to_string(),
             published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
             url: Some("https://dev.to/user/test-article".parse()?),
             tags: vec!["rust".to_string()],
             series: None,
             canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
             is_draft: false,
         };
 
-        let relative_path = writer.write_article(&article, &mut state)?;
+        let relative_path = writer.write_article(&article, &mut state)?.path;
         assert_eq!(relative_path, "devto/2024-03-15-test-article.md");
 
         // Check the file exists in the platform subdirectory
@@ -143,6 +712,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_article_sanitizes_paths() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Platform);
+        let mut state = PullState::default();
+
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::Custom {
+                domain: "forem.example.com:8443".to_string(),
+            }),
+            title: "CON".to_string(),
+            body_markdown: "Hello".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: true,
+        };
+
+        let relative_path = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(
+            relative_path,
+            "forem_custom_forem.example.com_8443/draft-con.md"
+        );
+        assert!(dir.path().join(&relative_path).exists());
+        assert_eq!(
+            state.get_local_path("forem:custom:forem.example.com:8443:123"),
+            Some(relative_path.as_str())
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_dry_run_does_not_write() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let dir = TempDir::new()?;
@@ -155,17 +764,733 @@ mod tests {
             title: "Test Article".to_string(),
             body_markdown: "Hello, world!".to_string(),
             published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
             url: None,
             tags: vec![],
             series: None,
             canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
             is_draft: false,
         };
 
-        let relative_path = writer.write_article(&article, &mut state)?;
-        let filepath = dir.path().join(&relative_path);
+        let outcome = writer.write_article(&article, &mut state)?;
+        let filepath = dir.path().join(&outcome.path);
         assert!(!filepath.exists());
         assert!(!state.is_pulled("devto:123"));
+        // What would have been written is still rendered, for --print
+        assert_eq!(outcome.content, article.to_markdown()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_date_hierarchy() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::DateHierarchy);
+        let mut state = PullState::default();
+
+        let article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Test Article".to_string(),
+            body_markdown: "Hello, world!".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
+            url: Some("https://dev.to/user/test-article".parse()?),
+            tags: vec!["rust".to_string()],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let relative_path = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(relative_path, "2024/03/2024-03-15-test-article.md");
+
+        let filepath = dir.path().join(&relative_path);
+        assert!(filepath.exists());
+
+        let month_dir = dir.path().join("2024").join("03");
+        assert!(month_dir.is_dir());
+
+        let content = std::fs::read_to_string(filepath)?;
+        assert!(content.contains("title: Test Article"));
+        assert!(content.contains("# Platform ID: devto:123"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_date_hierarchy_draft(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::DateHierarchy);
+        let mut state = PullState::default();
+
+        let article = PulledArticle {
+            platform_id: "456".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "My Draft".to_string(),
+            body_markdown: "Work in progress".to_string(),
+            published_at: None,
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: true,
+        };
+
+        let relative_path = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(relative_path, "drafts/draft-my-draft.md");
+        assert!(dir.path().join(&relative_path).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_series() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Series);
+        let mut state = PullState::default();
+
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Part Two".to_string(),
+            body_markdown: "Hello, world!".to_string(),
+            published_at: Some("2024-03-22T10:00:00Z".parse()?),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: Some("Rust CLI Series".to_string()),
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+        let second = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(second, "rust-cli-series/2024-03-22-part-two.md");
+
+        article.platform_id = "122".to_string();
+        article.title = "Part One".to_string();
+        article.published_at = Some("2024-03-15T10:00:00Z".parse()?);
+        writer.write_article(&article, &mut state)?;
+
+        article.platform_id = "200".to_string();
+        article.title = "Standalone".to_string();
+        article.series = None;
+        let standalone = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(standalone, "2024-03-15-standalone.md");
+
+        let series = BTreeSet::from(["Rust CLI Series".to_string()]);
+        let indexes = writer.write_series_indexes(&series)?;
+        assert_eq!(indexes, vec!["rust-cli-series/_index.md".to_string()]);
+
+        let index = std::fs::read_to_string(dir.path().join("rust-cli-series/_index.md"))?;
+        assert!(index.contains("title: Rust CLI Series"));
+        let first_pos = index
+            .find("1. [Part One](2024-03-15-part-one.md)")
+            .ok_or("missing part one")?;
+        let second_pos = index
+            .find("2. [Part Two](2024-03-22-part-two.md)")
+            .ok_or("missing part two")?;
+        assert!(first_pos < second_pos);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_outcome_status() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let mut state = PullState::default();
+
+        let mut article = PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Test Article".to_string(),
+            body_markdown: "Hello, world!".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        };
+
+        let created = writer.write_article(&article, &mut state)?;
+        assert_eq!(created.status, WriteStatus::Created);
+
+        let unchanged = writer.write_article(&article, &mut state)?;
+        assert_eq!(unchanged.status, WriteStatus::Unchanged);
+
+        article.body_markdown = "Edited".to_string();
+        let updated = writer.write_article(&article, &mut state)?;
+        assert_eq!(updated.status, WriteStatus::Updated);
+        assert_eq!(updated.path, created.path);
+
+        let dry_writer = Writer::new(dir.path(), true, FolderStructure::Flat);
+        let unchanged = dry_writer.write_article(&article, &mut state)?;
+        assert_eq!(unchanged.status, WriteStatus::Unchanged);
+
+        article.body_markdown = "Edited again".to_string();
+        let would_update = dry_writer.write_article(&article, &mut state)?;
+        assert_eq!(would_update.status, WriteStatus::WouldUpdate);
+        let diff = would_update
+            .diff
+            .ok_or("dry-run update should carry a diff")?;
+        assert!(diff.contains("--- a/2024-03-15-test-article.md"));
+        assert!(diff.contains("-Edited\n"));
+        assert!(diff.contains("+Edited again\n"));
+
+        article.title = "Brand New".to_string();
+        let would_write = dry_writer.write_article(&article, &mut state)?;
+        assert_eq!(would_write.status, WriteStatus::WouldWrite);
+        assert_eq!(would_write.diff, None);
+        assert!(!dir.path().join(&would_write.path).exists());
+        Ok(())
+    }
+
+    fn test_article() -> std::result::Result<PulledArticle, Box<dyn std::error::Error>> {
+        Ok(PulledArticle {
+            platform_id: "123".to_string(),
+            platform: Platform::Forem(ForemInstance::DevTo),
+            title: "Test Article".to_string(),
+            body_markdown: "From the platform".to_string(),
+            published_at: Some("2024-03-15T10:00:00Z".parse()?),
+            edited_at: None,
+            url: None,
+            tags: vec![],
+            series: None,
+            canonical_url: None,
+            cover_image: None,
+            author: None,
+            organization: None,
+            stats: ArticleStats::default(),
+            etag: None,
+            is_draft: false,
+        })
+    }
+
+    fn edited_locally(
+        dir: &Path,
+    ) -> std::result::Result<PulledArticle, Box<dyn std::error::Error>> {
+        std::fs::write(dir.join("2024-03-15-test-article.md"), "My local edits\n")?;
+        test_article()
+    }
+
+    #[test]
+    fn test_skip_keeps_existing_file() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let article = edited_locally(dir.path())?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_overwrite_mode(OverwriteMode::Skip);
+        let mut state = PullState::default();
+
+        let outcome = writer.write_article(&article, &mut state)?;
+
+        assert_eq!(outcome.status, WriteStatus::Skipped);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(&outcome.path))?,
+            "My local edits\n"
+        );
+        assert!(!state.is_pulled("devto:123"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_renames_existing_file() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let article = edited_locally(dir.path())?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_overwrite_mode(OverwriteMode::Backup);
+        let mut state = PullState::default();
+
+        let outcome = writer.write_article(&article, &mut state)?;
+
+        assert_eq!(outcome.status, WriteStatus::Updated);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("2024-03-15-test-article.md.bak"))?,
+            "My local edits\n"
+        );
+        assert!(
+            std::fs::read_to_string(dir.path().join(&outcome.path))?.contains("From the platform")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_overwrite_replaces_edited_file() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = TempDir::new()?;
+        let mut article = test_article()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_overwrite_mode(OverwriteMode::Overwrite);
+        let mut state = PullState::default();
+        let path = writer.write_article(&article, &mut state)?.path;
+        std::fs::write(dir.path().join(&path), "My local edits\n")?;
+
+        article.body_markdown = "Edited on the platform".to_string();
+        let outcome = writer.write_article(&article, &mut state)?;
+
+        assert_eq!(outcome.status, WriteStatus::Updated);
+        assert!(std::fs::read_to_string(dir.path().join(&path))?.contains("Edited on the platform"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_if_changed_keeps_locally_edited_file(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let mut article = test_article()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let mut state = PullState::default();
+        let path = writer.write_article(&article, &mut state)?.path;
+
+        // Untouched since the pull: the platform version replaces it
+        article.body_markdown = "Edited on the platform".to_string();
+        let outcome = writer.write_article(&article, &mut state)?;
+        assert_eq!(outcome.status, WriteStatus::Updated);
+
+        // Edited locally since: kept, and still offered next run
+        std::fs::write(dir.path().join(&path), "My local edits\n")?;
+        article.body_markdown = "Edited again".to_string();
+        let pulled_at = state.pulled["devto:123"].pulled_at;
+        let outcome = writer.write_article(&article, &mut state)?;
+        assert_eq!(outcome.status, WriteStatus::Skipped);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(&path))?,
+            "My local edits\n"
+        );
+        assert_eq!(state.pulled["devto:123"].pulled_at, pulled_at);
+
+        // A file the state knows nothing about is replaced
+        let outcome = writer.write_article(&article, &mut PullState::default())?;
+        assert_eq!(outcome.status, WriteStatus::Updated);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_applies_overwrite_mode() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = TempDir::new()?;
+        let article = edited_locally(dir.path())?;
+        let mut state = PullState::default();
+
+        let skip = Writer::new(dir.path(), true, FolderStructure::Flat)
+            .with_overwrite_mode(OverwriteMode::Skip);
+        assert_eq!(
+            skip.write_article(&article, &mut state)?.status,
+            WriteStatus::Skipped
+        );
+
+        let backup = Writer::new(dir.path(), true, FolderStructure::Flat)
+            .with_overwrite_mode(OverwriteMode::Backup);
+        let outcome = backup.write_article(&article, &mut state)?;
+        assert_eq!(outcome.status, WriteStatus::WouldUpdate);
+        assert!(!dir.path().join("2024-03-15-test-article.md.bak").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(&outcome.path))?,
+            "My local edits\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_endings() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let mut state = PullState::default();
+        let mut article = test_article()?;
+        article.body_markdown = "First line\r\nSecond line\n".to_string();
+
+        let lf = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let path = lf.write_article(&article, &mut state)?.path;
+        let content = std::fs::read_to_string(dir.path().join(&path))?;
+        assert!(!content.contains('\r'));
+        assert!(content.starts_with("---\ntitle:"));
+        assert!(content.contains("First line\nSecond line\n"));
+
+        let crlf = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_line_endings(LineEndings::Crlf);
+        let outcome = crlf.write_article(&article, &mut state)?;
+        assert_eq!(outcome.status, WriteStatus::Updated);
+        let content = std::fs::read_to_string(dir.path().join(&path))?;
+        assert!(content.starts_with("---\r\ntitle:"));
+        assert!(content.contains("First line\r\nSecond line\r\n"));
+        assert!(!content.replace("\r\n", "").contains('\n'));
+
+        let preserve = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_line_endings(LineEndings::Preserve);
+        preserve.write_article(&article, &mut state)?;
+        let content = std::fs::read_to_string(dir.path().join(&path))?;
+        assert!(content.starts_with("---\ntitle:"));
+        assert!(content.contains("First line\r\nSecond line\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_hugo_bundle() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::HugoBundle);
+        let mut state = PullState::default();
+
+        let outcome = writer.write_article(&test_article()?, &mut state)?;
+        assert_eq!(outcome.path, "2024-03-15-test-article/index.md");
+
+        let content = std::fs::read_to_string(dir.path().join(&outcome.path))?;
+        assert!(content.contains("title: Test Article\n"));
+        assert!(content.contains("date: 2024-03-15T10:00:00Z\n"));
+        assert!(content.contains("draft: false\n"));
+        assert!(!content.contains("status:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_frontmatter_format() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::HugoBundle)
+            .with_frontmatter_format(Some(FrontmatterFormat::Forem));
+        let mut state = PullState::default();
+
+        let outcome = writer.write_article(&test_article()?, &mut state)?;
+        assert_eq!(outcome.path, "2024-03-15-test-article/index.md");
+
+        let content = std::fs::read_to_string(dir.path().join(&outcome.path))?;
+        assert!(content.contains("published: true\n"));
+        assert!(!content.contains("draft:"));
+        assert!(!content.contains("date:"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_images_into_bundle(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/img/photo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(b"PNG".to_vec(), "image/png"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::HugoBundle)
+            .with_image_downloads(reqwest::Client::new());
+        let mut article = test_article()?;
+        let photo = format!("{}/img/photo.png", server.uri());
+        let missing = format!("{}/img/missing.png", server.uri());
+        article.body_markdown = format!("![Photo]({photo})\n\n![Gone]({missing})\n");
+
+        assert_eq!(writer.download_images(&mut article).await?, 1);
+        assert_eq!(
+            article.body_markdown,
+            format!("![Photo](01-photo.png)\n\n![Gone]({missing})\n")
+        );
+        assert_eq!(
+            std::fs::read(dir.path().join("2024-03-15-test-article/01-photo.png"))?,
+            b"PNG"
+        );
+
+        // Already downloaded images are reused without another request
+        let mut again = test_article()?;
+        again.body_markdown = format!("![Photo]({photo})\n");
+        assert_eq!(writer.download_images(&mut again).await?, 0);
+        assert_eq!(again.body_markdown, "![Photo](01-photo.png)\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_images_next_to_flat_article(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/photo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(b"PNG".to_vec(), "image/png"))
+            .mount(&server)
+            .await;
+
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat)
+            .with_image_downloads(reqwest::Client::new());
+        let mut article = test_article()?;
+        article.body_markdown = format!("![Photo]({}/photo.png)", server.uri());
+
+        writer.download_images(&mut article).await?;
+        assert_eq!(
+            article.body_markdown,
+            "![Photo](images/2024-03-15-test-article/01-photo.png)"
+        );
+        assert!(dir
+            .path()
+            .join("images/2024-03-15-test-article/01-photo.png")
+            .exists());
+        Ok(())
+    }
+
+    /// Serves `/broken.png` as a 500 and any other path as a PNG holding its file
+    /// name, holding back every response until `gate` requests are open at once.
+    ///
+    /// Returns the server's base URL and the paths requested so far.
+    async fn serve_images_once_concurrent(
+        mut gate: usize,
+    ) -> std::io::Result<(String, Arc<Mutex<Vec<String>>>)> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let uri = format!("http://{}", listener.local_addr()?);
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&requested);
+
+        tokio::spawn(async move {
+            let mut waiting = Vec::new();
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let path = String::from_utf8_lossy(&request)
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+                log.lock()
+                    .expect("request log lock poisoned")
+                    .push(path.clone());
+                waiting.push((socket, path));
+
+                if waiting.len() < gate {
+                    continue;
+                }
+                // Every later request is answered straight away
+                gate = 1;
+                for (mut socket, path) in waiting.drain(..) {
+                    let response = match path.strip_prefix('/') {
+                        Some("broken.png") => "HTTP/1.1 500 Internal Server Error\r\n\
+                                               Content-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string(),
+                        name => {
+                            let name = name.unwrap_or_default();
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\
+                                 Content-Length: {}\r\nConnection: close\r\n\r\n{name}",
+                                name.len()
+                            )
+                        }
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                }
+            }
+        });
+        Ok((uri, requested))
+    }
+
+    #[tokio::test]
+    async fn test_download_images_concurrently(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        // Downloads one after another would never have three requests open
+        let (uri, requested) = serve_images_once_concurrent(3).await?;
+
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::HugoBundle)
+            .with_image_downloads(reqwest::Client::new());
+        let mut article = test_article()?;
+        article.body_markdown = format!(
+            "![A]({uri}/a.png) ![B]({uri}/b.png)\n![A again]({uri}/a.png)\n\
+             ![Broken]({uri}/broken.png) ![C]({uri}/c.png)\n"
+        );
+
+        // Only bounds a regression that would otherwise hang the test
+        let downloaded = tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            writer.download_images(&mut article),
+        )
+        .await??;
+        assert_eq!(downloaded, 3);
+
+        let mut requested = requested.lock().expect("request log lock poisoned").clone();
+        requested.sort();
+        assert_eq!(requested, ["/a.png", "/b.png", "/broken.png", "/c.png"]);
+        assert_eq!(
+            article.body_markdown,
+            format!(
+                "![A](01-a.png) ![B](02-b.png)\n![A again](01-a.png)\n\
+                 ![Broken]({uri}/broken.png) ![C](04-c.png)\n"
+            )
+        );
+        let bundle = dir.path().join("2024-03-15-test-article");
+        assert_eq!(std::fs::read(bundle.join("04-c.png"))?, b"c.png");
+        assert!(!bundle.join("03-broken.png").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_image_extension_from_content_type(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        for (image_path, mime) in [
+            ("/image/upload/abc123", "image/jpeg"),
+            ("/photo.png", "image/webp"),
+            ("/blob", "application/octet-stream"),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(image_path))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(b"IMG".to_vec(), mime))
+                .mount(&server)
+                .await;
+        }
+
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::HugoBundle)
+            .with_image_downloads(reqwest::Client::new());
+        let uri = server.uri();
+        let mut article = test_article()?;
+        article.body_markdown =
+            format!("![A]({uri}/image/upload/abc123)\n![B]({uri}/photo.png)\n![C]({uri}/blob)\n");
+
+        assert_eq!(writer.download_images(&mut article).await?, 3);
+        assert_eq!(
+            article.body_markdown,
+            "![A](01-abc123.jpg)\n![B](02-photo.webp)\n![C](03-blob.bin)\n"
+        );
+
+        // The saved extension is found again without knowing the content type
+        let mut again = test_article()?;
+        again.body_markdown = format!("![A]({uri}/image/upload/abc123)\n");
+        assert_eq!(writer.download_images(&mut again).await?, 0);
+        assert_eq!(again.body_markdown, "![A](01-abc123.jpg)\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_tag() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Tag);
+        let mut state = PullState::default();
+
+        let mut article = test_article()?;
+        article.tags = vec!["Web Dev".to_string(), "rust".to_string()];
+        let tagged = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(tagged, "web-dev/2024-03-15-test-article.md");
+        assert!(!dir.path().join("rust").exists());
+
+        article.platform_id = "124".to_string();
+        article.tags = vec![];
+        let untagged = writer.write_article(&article, &mut state)?.path;
+        assert_eq!(untagged, "untagged/2024-03-15-test-article.md");
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_jekyll() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Jekyll);
+        let mut state = PullState::default();
+
+        let mut article = test_article()?;
+        article.tags = vec!["rust".to_string()];
+        article.series = Some("Rust CLI Series".to_string());
+        let outcome = writer.write_article(&article, &mut state)?;
+        assert_eq!(outcome.path, "_posts/2024-03-15-test-article.md");
+
+        let content = std::fs::read_to_string(dir.path().join(&outcome.path))?;
+        assert!(content.starts_with("---\nlayout: post\ntitle: Test Article\n"));
+        assert!(content.contains("date: 2024-03-15T10:00:00Z\n"));
+        assert!(content.contains("categories:\n- Rust CLI Series\n"));
+        assert!(content.contains("tags:\n- rust\n"));
+        assert!(!content.contains("status:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_jekyll_draft() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Jekyll);
+        let mut state = PullState::default();
+
+        let mut article = test_article()?;
+        article.published_at = None;
+        article.is_draft = true;
+        let outcome = writer.write_article(&article, &mut state)?;
+        assert_eq!(outcome.path, "_drafts/test-article.md");
+
+        let content = std::fs::read_to_string(dir.path().join(&outcome.path))?;
+        assert!(content.starts_with("---\nlayout: post\ntitle: Test Article\n"));
+        assert!(!content.contains("date:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_title_with_control_characters(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let writer = Writer::new(dir.path(), false, FolderStructure::Flat);
+        let mut state = PullState::default();
+
+        let mut article = test_article()?;
+        article.title = "Tabs\tbells\u{7} and\u{1b} escapes".to_string();
+        let outcome = writer.write_article(&article, &mut state)?;
+
+        let content = std::fs::read_to_string(dir.path().join(&outcome.path))?;
+        assert_eq!(read_frontmatter_title(&content), Some(article.title));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_article_id_in_frontmatter() -> std::result::Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = TempDir::new()?;
+        let mut state = PullState::default();
+
+        let writer = Writer::new(dir.path(), true, FolderStructure::Flat);
+        let content = writer.write_article(&test_article()?, &mut state)?.content;
+        assert!(!content.contains("\nid:"));
+
+        let writer = Writer::new(dir.path(), true, FolderStructure::Flat)
+            .with_tracking_marker(false)
+            .with_id_in_frontmatter(true);
+        let content = writer.write_article(&test_article()?, &mut state)?.content;
+        assert!(content.starts_with("---\nid: '123'\ntitle: Test Article\n"));
+        assert!(!content.contains("# Platform ID"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_path_is_a_file() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let file = dir.path().join("output");
+        std::fs::write(&file, "not a directory")?;
+
+        for dry_run in [false, true] {
+            let result = Writer::new(&file, dry_run, FolderStructure::Flat).ensure_output_dir();
+            assert!(matches!(
+                result,
+                Err(PullError::InvalidConfig(message)) if message.contains("is not a directory")
+            ));
+        }
         Ok(())
     }
 }